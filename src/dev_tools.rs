@@ -6,6 +6,7 @@ use crate::screen::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(crate::ui::inspector::plugin);
+    app.add_plugins(crate::game::platter::debug_overlay::plugin);
     // Print state transitions in dev builds
     app.add_systems(Update, log_transitions::<Screen>);
 }
@@ -40,7 +40,7 @@ impl FromWorld for HandleMap<ImageKey> {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect)]
 pub enum SfxKey {
     ButtonHover,
     ButtonPress,
@@ -48,6 +48,13 @@ pub enum SfxKey {
     Step2,
     Step3,
     Step4,
+    BlockSpawn,
+    PlatterMove,
+    PlatterRotate,
+    BlockLand,
+    RingClear,
+    HardDrop,
+    GameOver,
 }
 
 impl AssetKey for SfxKey {
@@ -70,12 +77,36 @@ impl FromWorld for HandleMap<SfxKey> {
             (SfxKey::Step2, asset_server.load("audio/sfx/step2.ogg")),
             (SfxKey::Step3, asset_server.load("audio/sfx/step3.ogg")),
             (SfxKey::Step4, asset_server.load("audio/sfx/step4.ogg")),
+            // placeholders until the gameplay sounds have their own files, `SfxKey::profile`
+            // pitches them apart
+            (
+                SfxKey::BlockSpawn,
+                asset_server.load("audio/sfx/button_hover.ogg"),
+            ),
+            (
+                SfxKey::PlatterMove,
+                asset_server.load("audio/sfx/step1.ogg"),
+            ),
+            (
+                SfxKey::PlatterRotate,
+                asset_server.load("audio/sfx/step3.ogg"),
+            ),
+            (SfxKey::BlockLand, asset_server.load("audio/sfx/step2.ogg")),
+            (
+                SfxKey::RingClear,
+                asset_server.load("audio/sfx/button_press.ogg"),
+            ),
+            (SfxKey::HardDrop, asset_server.load("audio/sfx/step4.ogg")),
+            (
+                SfxKey::GameOver,
+                asset_server.load("audio/sfx/button_press.ogg"),
+            ),
         ]
         .into()
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect)]
 pub enum SoundtrackKey {
     Credits,
     Gameplay,
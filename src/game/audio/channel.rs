@@ -0,0 +1,203 @@
+//! Music, SFX and UI volume channels. Every sound carries a [`ChannelVolume`], its sink volume is
+//! recomputed each frame so channel, master volume and ducking changes apply to playing sounds.
+
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<AudioChannels>();
+    app.init_resource::<DuckLevel>();
+    app.add_systems(
+        Update,
+        (
+            update_duck_level,
+            update_sink_volumes::<AudioSink>,
+            update_sink_volumes::<SpatialAudioSink>,
+        )
+            .chain(),
+    );
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect, AutoRegisterType)]
+pub enum AudioChannel {
+    Music,
+    #[default]
+    Sfx,
+    Ui,
+}
+
+/// Volume of each channel, multiplied with [`GlobalVolume`].
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct AudioChannels {
+    #[default(1.0)]
+    pub music: f32,
+    #[default(1.0)]
+    pub sfx: f32,
+    #[default(1.0)]
+    pub ui: f32,
+    pub ducking: Ducking,
+}
+
+impl AudioChannels {
+    pub fn get(&self, channel: AudioChannel) -> f32 {
+        match channel {
+            AudioChannel::Music => self.music,
+            AudioChannel::Sfx => self.sfx,
+            AudioChannel::Ui => self.ui,
+        }
+    }
+}
+
+/// Lowers the music while any [`DucksMusic`] sound is playing.
+#[derive(Debug, SmartDefault, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub struct Ducking {
+    #[default(true)]
+    pub enabled: bool,
+    /// Music volume multiplier while ducked.
+    #[default(0.35)]
+    pub volume: f32,
+    /// Seconds to fade the music down.
+    #[default(0.1)]
+    pub attack: f32,
+    /// Seconds to fade the music back up.
+    #[default(0.6)]
+    pub release: f32,
+}
+
+impl Ducking {
+    /// Moves `level` towards `target` without overshooting, at the attack or release rate.
+    fn step(&self, level: f32, target: f32, delta_seconds: f32) -> f32 {
+        let duration = if target < level {
+            self.attack
+        } else {
+            self.release
+        };
+        let max_step = if duration > 0.0 {
+            (1.0 - self.volume).abs().max(f32::EPSILON) * delta_seconds / duration
+        } else {
+            f32::INFINITY
+        };
+        level + (target - level).clamp(-max_step, max_step)
+    }
+}
+
+/// Channel and volume of a sound, before the channel and global volume are applied.
+#[derive(Component, Debug, SmartDefault, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct ChannelVolume {
+    pub channel: AudioChannel,
+    #[default(1.0)]
+    pub volume: f32,
+}
+
+impl ChannelVolume {
+    pub fn new(channel: AudioChannel) -> Self {
+        Self {
+            channel,
+            ..default()
+        }
+    }
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+}
+
+/// Marks an important sound that lowers the music while it plays, see [`Ducking`].
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct DucksMusic;
+
+/// Current music multiplier from ducking, `1.0` when not ducked.
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct DuckLevel(#[default(1.0)] f32);
+
+/// Computes the volume of sounds from their [`ChannelVolume`].
+#[derive(SystemParam)]
+pub struct AudioMixer<'w> {
+    global_volume: Res<'w, GlobalVolume>,
+    channels: Res<'w, AudioChannels>,
+    duck_level: Res<'w, DuckLevel>,
+}
+
+impl AudioMixer<'_> {
+    /// For [`PlaybackSettings::volume`], Bevy applies the [`GlobalVolume`] when the sound starts.
+    pub fn playback_volume(&self, channel_volume: &ChannelVolume) -> Volume {
+        Volume::new(self.channel_volume(channel_volume))
+    }
+    /// For [`AudioSinkPlayback::set_volume`], which replaces the volume including the global one.
+    pub fn sink_volume(&self, channel_volume: &ChannelVolume) -> f32 {
+        self.global_volume.volume.get() * self.channel_volume(channel_volume)
+    }
+    fn channel_volume(&self, channel_volume: &ChannelVolume) -> f32 {
+        let duck = match channel_volume.channel {
+            AudioChannel::Music => self.duck_level.0,
+            _ => 1.0,
+        };
+        channel_volume.volume * self.channels.get(channel_volume.channel) * duck
+    }
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn update_duck_level(
+    time: Res<Time<Real>>,
+    channels: Res<AudioChannels>,
+    ducking_q: Query<(), With<DucksMusic>>,
+    mut duck_level: ResMut<DuckLevel>,
+) {
+    let ducking = channels.ducking;
+    let target = if ducking.enabled && !ducking_q.is_empty() {
+        ducking.volume
+    } else {
+        1.0
+    };
+    if duck_level.0 == target {
+        return;
+    }
+    duck_level.0 = ducking.step(duck_level.0, target, time.delta_seconds());
+}
+
+fn update_sink_volumes<S: Component + AudioSinkPlayback>(
+    mixer: AudioMixer,
+    sink_q: Query<(&S, &ChannelVolume)>,
+) {
+    for (sink, channel_volume) in sink_q.iter() {
+        let volume = mixer.sink_volume(channel_volume);
+        if (sink.volume() - volume).abs() > f32::EPSILON {
+            sink.set_volume(volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ducking_step() {
+        let ducking = Ducking {
+            volume: 0.5,
+            attack: 0.1,
+            release: 1.0,
+            ..default()
+        };
+        // full attack in 0.1 seconds
+        let level = ducking.step(1.0, 0.5, 0.05);
+        assert!((level - 0.75).abs() < 1e-5);
+        assert_eq!(ducking.step(level, 0.5, 1.0), 0.5);
+        // slower release, never past the target
+        let level = ducking.step(0.5, 1.0, 0.5);
+        assert!((level - 0.75).abs() < 1e-5);
+        assert_eq!(ducking.step(level, 1.0, 10.0), 1.0);
+    }
+}
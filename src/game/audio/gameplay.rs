@@ -0,0 +1,94 @@
+//! Plays sound effects for platter events through [`PlaySfx`], positioned where they happened.
+
+use bevy::prelude::*;
+
+use crate::game::assets::SfxKey;
+use crate::game::audio::sfx::PlaySfx;
+use crate::game::platter::clear::{ClearSystemSet, SegmentsCleared};
+use crate::game::platter::controls::{PlatterInput, PlatterInputKind};
+use crate::game::platter::falling::{BlockLanded, FallingBlockSpawned};
+use crate::game::platter::game_over::PlatterGameOver;
+use crate::game::platter::segment::CenterPoint;
+use crate::game::platter::spin::SpinDirectionChanged;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, sfx_from_gameplay.after(ClearSystemSet));
+}
+
+/// Semitones the ring clear rises per chain step, capped after [`MAX_COMBO_STEPS`].
+const COMBO_SEMITONES: f32 = 2.0;
+const MAX_COMBO_STEPS: usize = 12;
+
+/// Pitch multiplier of the ring clear sound for the `chain`th clear of a cascade.
+fn combo_pitch(chain: usize) -> f32 {
+    2f32.powf(chain.min(MAX_COMBO_STEPS) as f32 * COMBO_SEMITONES / 12.0)
+}
+
+fn sfx_from_gameplay(
+    mut commands: Commands,
+    mut falling_block_spawned: EventReader<FallingBlockSpawned>,
+    mut platter_input: EventReader<PlatterInput>,
+    mut spin_direction_changed: EventReader<SpinDirectionChanged>,
+    mut block_landed: EventReader<BlockLanded>,
+    mut segments_cleared: EventReader<SegmentsCleared>,
+    mut platter_game_over: EventReader<PlatterGameOver>,
+    transform_q: Query<&GlobalTransform>,
+    segment_q: Query<&CenterPoint>,
+) {
+    let at_platter = |sfx: PlaySfx, platter: Entity| match transform_q.get(platter) {
+        Ok(transform) => sfx.with_position(transform.translation().truncate()),
+        Err(_) => sfx,
+    };
+    for event in falling_block_spawned.read() {
+        commands.trigger(at_platter(PlaySfx::key(SfxKey::BlockSpawn), event.platter));
+    }
+    for event in platter_input.read() {
+        let sfx_key = match event.kind {
+            PlatterInputKind::SpinLeft | PlatterInputKind::SpinRight => SfxKey::PlatterMove,
+            PlatterInputKind::Drop => SfxKey::HardDrop,
+        };
+        commands.trigger(at_platter(PlaySfx::key(sfx_key), event.platter));
+    }
+    for event in spin_direction_changed.read() {
+        commands.trigger(at_platter(
+            PlaySfx::key(SfxKey::PlatterRotate),
+            event.platter,
+        ));
+    }
+    for event in block_landed.read() {
+        commands.trigger(at_platter(PlaySfx::key(SfxKey::BlockLand), event.platter));
+    }
+    for event in platter_game_over.read() {
+        commands.trigger(at_platter(PlaySfx::key(SfxKey::GameOver), event.platter));
+    }
+    for event in segments_cleared.read() {
+        let sfx = PlaySfx::key(SfxKey::RingClear).with_pitch(combo_pitch(event.chain));
+        // full rings are centred on the platter, match clears come from their segments
+        let centers = segment_q
+            .iter_many(&event.segments)
+            .map(CenterPoint::get)
+            .collect::<Vec<_>>();
+        if centers.is_empty() {
+            commands.trigger(at_platter(sfx, event.platter));
+        } else {
+            let center = centers.iter().sum::<Vec2>() / centers.len() as f32;
+            commands.trigger(sfx.with_position(center));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo_pitch_rises() {
+        assert_eq!(combo_pitch(0), 1.0);
+        assert!((combo_pitch(6) - 2.0).abs() < 1e-5);
+        assert!(combo_pitch(1) > combo_pitch(0));
+        assert_eq!(
+            combo_pitch(MAX_COMBO_STEPS + 5),
+            combo_pitch(MAX_COMBO_STEPS)
+        );
+    }
+}
@@ -1,8 +1,15 @@
 use bevy::prelude::*;
 
+pub mod channel;
+mod gameplay;
 pub mod sfx;
 pub mod soundtrack;
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins((sfx::plugin, soundtrack::plugin));
+    app.add_plugins((
+        channel::plugin,
+        gameplay::plugin,
+        sfx::plugin,
+        soundtrack::plugin,
+    ));
 }
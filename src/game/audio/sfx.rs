@@ -1,33 +1,164 @@
-use bevy::{audio::PlaybackMode, prelude::*};
-use rand::seq::SliceRandom;
+use bevy::{
+    audio::{PlaybackMode, SpatialScale},
+    prelude::*,
+};
+use rand::{seq::SliceRandom, Rng};
 
 use crate::game::assets::{HandleMap, SfxKey};
+use crate::game::audio::channel::{AudioChannel, AudioMixer, ChannelVolume, DucksMusic};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(play_sfx);
 }
 
+/// World units between the ears of the [`SpatialListener`] on the main camera.
+pub const LISTENER_EAR_GAP: f32 = 400.0;
+
+/// Positioned sounds are at full volume within `1.0 / SPATIAL_SCALE` world units of an ear and
+/// fall off with the square of the distance beyond that.
+const SPATIAL_SCALE: f32 = 1.0 / 300.0;
+
 fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    mixer: AudioMixer,
+    listener_q: Query<&GlobalTransform, With<SpatialListener>>,
 ) {
-    let sfx_key = match trigger.event() {
-        PlaySfx::Key(key) => *key,
-        PlaySfx::RandomStep => random_step(),
+    let event = trigger.event();
+    let sfx_key = match event.sound {
+        SfxSound::Key(key) => key,
+        SfxSound::RandomStep => random_step(),
     };
-    commands.spawn(AudioSourceBundle {
-        source: sfx_handles[&sfx_key].clone_weak(),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            ..default()
+    let profile = sfx_key.profile();
+    let mut rng = rand::thread_rng();
+    let mut vary = |variation: f32| 1.0 + rng.gen_range(-variation..=variation);
+    let speed = profile.pitch * event.pitch * vary(profile.pitch_variation);
+    let volume = profile.volume * vary(profile.volume_variation);
+    let channel_volume = ChannelVolume::new(sfx_key.channel()).with_volume(volume);
+    let mut entity_commands = commands.spawn((
+        AudioSourceBundle {
+            source: sfx_handles[&sfx_key].clone_weak(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: mixer.playback_volume(&channel_volume),
+                speed,
+                spatial: event.position.is_some(),
+                spatial_scale: Some(SpatialScale::new_2d(SPATIAL_SCALE)),
+                ..default()
+            },
         },
-    });
+        channel_volume,
+    ));
+    if let Some(position) = event.position {
+        // the camera sits far above the platters, only the distance across the screen should count
+        let listener_z = listener_q
+            .get_single()
+            .map_or(0.0, |listener| listener.translation().z);
+        entity_commands.insert(TransformBundle::from_transform(
+            Transform::from_translation(position.extend(listener_z)),
+        ));
+    }
+    if sfx_key.ducks_music() {
+        entity_commands.insert(DucksMusic);
+    }
+}
+
+/// Base pitch and volume of a sound, randomised by up to the given fractions every time it plays
+/// so repeated sounds don't get tiring.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SfxProfile {
+    pub pitch: f32,
+    pub volume: f32,
+    pub pitch_variation: f32,
+    pub volume_variation: f32,
+}
+
+impl SfxProfile {
+    const fn new(pitch: f32, volume: f32) -> Self {
+        Self {
+            pitch,
+            volume,
+            pitch_variation: 0.0,
+            volume_variation: 0.0,
+        }
+    }
+    const fn varied(self, pitch_variation: f32, volume_variation: f32) -> Self {
+        Self {
+            pitch_variation,
+            volume_variation,
+            ..self
+        }
+    }
+}
+
+impl SfxKey {
+    pub fn channel(self) -> AudioChannel {
+        match self {
+            SfxKey::ButtonHover | SfxKey::ButtonPress => AudioChannel::Ui,
+            _ => AudioChannel::Sfx,
+        }
+    }
+    /// Important sounds lower the music while they play.
+    pub fn ducks_music(self) -> bool {
+        matches!(self, SfxKey::RingClear | SfxKey::GameOver)
+    }
+    pub fn profile(self) -> SfxProfile {
+        match self {
+            SfxKey::ButtonHover | SfxKey::ButtonPress => SfxProfile::new(1.0, 1.0),
+            SfxKey::Step1 | SfxKey::Step2 | SfxKey::Step3 | SfxKey::Step4 => {
+                SfxProfile::new(1.0, 1.0).varied(0.1, 0.1)
+            }
+            SfxKey::BlockSpawn => SfxProfile::new(1.5, 0.6).varied(0.08, 0.1),
+            SfxKey::PlatterMove => SfxProfile::new(1.2, 0.5).varied(0.1, 0.15),
+            SfxKey::PlatterRotate => SfxProfile::new(0.7, 0.8).varied(0.05, 0.1),
+            SfxKey::BlockLand => SfxProfile::new(0.8, 1.0).varied(0.1, 0.15),
+            // the combo raises the pitch, keep the variation small
+            SfxKey::RingClear => SfxProfile::new(1.0, 1.0).varied(0.03, 0.05),
+            SfxKey::HardDrop => SfxProfile::new(0.6, 1.0).varied(0.08, 0.1),
+            SfxKey::GameOver => SfxProfile::new(0.5, 1.0),
+        }
+    }
 }
 
 /// Trigger this event to play a single sound effect.
-#[derive(Event)]
-pub enum PlaySfx {
+#[derive(Event, Debug, Copy, Clone)]
+pub struct PlaySfx {
+    pub sound: SfxSound,
+    /// Multiplied with the pitch of the sound, before the random variation.
+    pub pitch: f32,
+    /// World position the sound comes from, panned and attenuated by its distance to the main
+    /// camera.
+    pub position: Option<Vec2>,
+}
+
+impl PlaySfx {
+    pub fn key(key: SfxKey) -> Self {
+        Self {
+            sound: SfxSound::Key(key),
+            pitch: 1.0,
+            position: None,
+        }
+    }
+    pub fn random_step() -> Self {
+        Self {
+            sound: SfxSound::RandomStep,
+            pitch: 1.0,
+            position: None,
+        }
+    }
+    pub fn with_pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SfxSound {
     Key(SfxKey),
     RandomStep,
 }
@@ -1,48 +1,288 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use std::time::Duration;
+
+use bevy::{
+    audio::{AudioSinkPlayback, PlaybackMode},
+    prelude::*,
+};
+use rand::seq::SliceRandom;
+use smart_default::SmartDefault;
 
 use crate::game::assets::{HandleMap, SoundtrackKey};
+use crate::game::audio::channel::{AudioChannel, AudioMixer, ChannelVolume};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<IsSoundtrack>();
+    app.register_type::<SoundtrackFade>();
+    app.register_type::<SoundtrackSettings>();
+    app.init_resource::<SoundtrackSettings>();
+    app.init_resource::<ActivePlaylist>();
     app.observe(play_soundtrack);
+    app.add_systems(Update, (fade_soundtracks, advance_playlist).chain());
 }
 
 fn play_soundtrack(
     trigger: Trigger<PlaySoundtrack>,
     mut commands: Commands,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-    soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    soundtrack_query: Query<(Entity, &ChannelVolume), With<IsSoundtrack>>,
+    settings: Res<SoundtrackSettings>,
+    mut active_playlist: ResMut<ActivePlaylist>,
+    mixer: AudioMixer,
 ) {
-    for entity in &soundtrack_query {
-        commands.entity(entity).despawn_recursive();
+    // the old soundtrack fades out while the new one fades in
+    for (entity, channel_volume) in &soundtrack_query {
+        commands
+            .entity(entity)
+            .remove::<IsSoundtrack>()
+            .insert(SoundtrackFade::out(
+                channel_volume.volume,
+                settings.crossfade,
+            ));
     }
+    active_playlist.0 = None;
 
-    let soundtrack_key = match trigger.event() {
-        PlaySoundtrack::Key(key) => *key,
+    let (soundtrack_key, mode) = match trigger.event() {
+        PlaySoundtrack::Key(key) => (*key, PlaybackMode::Loop),
+        PlaySoundtrack::Playlist(playlist) => {
+            let mut state = PlaylistState::new(playlist.clone());
+            let Some(key) = state.next_key() else {
+                return;
+            };
+            active_playlist.0 = Some(state);
+            (key, PlaybackMode::Once)
+        }
         PlaySoundtrack::Disable => return,
     };
+    spawn_soundtrack(
+        &mut commands,
+        &soundtrack_handles,
+        &mixer,
+        soundtrack_key,
+        mode,
+        settings.crossfade,
+    );
+}
+
+fn spawn_soundtrack(
+    commands: &mut Commands,
+    soundtrack_handles: &HandleMap<SoundtrackKey>,
+    mixer: &AudioMixer,
+    soundtrack_key: SoundtrackKey,
+    mode: PlaybackMode,
+    fade_in: f32,
+) {
+    let channel_volume = ChannelVolume::new(AudioChannel::Music).with_volume(0.0);
     commands.spawn((
         AudioSourceBundle {
             source: soundtrack_handles[&soundtrack_key].clone_weak(),
             settings: PlaybackSettings {
-                mode: PlaybackMode::Loop,
+                mode,
+                volume: mixer.playback_volume(&channel_volume),
                 ..default()
             },
         },
+        channel_volume,
+        SoundtrackFade::new(0.0, 1.0, fade_in),
         IsSoundtrack,
     ));
 }
 
 /// Trigger this event to play or disable the soundtrack.
-/// Playing a new soundtrack will overwrite the previous one.
-/// Soundtracks will loop.
-#[derive(Event)]
+/// Playing a new soundtrack will crossfade from the previous one.
+/// Single soundtracks will loop, playlists repeat once every track was played.
+#[derive(Event, Debug, Clone)]
 pub enum PlaySoundtrack {
     Key(SoundtrackKey),
+    Playlist(Playlist),
     Disable,
 }
 
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct SoundtrackSettings {
+    /// Seconds the old soundtrack fades out while the new one fades in.
+    #[default(1.5)]
+    pub crossfade: f32,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum PlaylistOrder {
+    #[default]
+    Sequential,
+    /// Reshuffled every time the playlist repeats.
+    Shuffled,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Playlist {
+    pub tracks: Vec<SoundtrackKey>,
+    pub order: PlaylistOrder,
+    /// Seconds of silence between tracks.
+    pub gap: f32,
+}
+
+impl Playlist {
+    pub fn new(tracks: impl IntoIterator<Item = SoundtrackKey>) -> Self {
+        Self {
+            tracks: tracks.into_iter().collect(),
+            ..default()
+        }
+    }
+    pub fn shuffled(mut self) -> Self {
+        self.order = PlaylistOrder::Shuffled;
+        self
+    }
+    pub fn with_gap(mut self, seconds: f32) -> Self {
+        self.gap = seconds;
+        self
+    }
+}
+
 /// Marker component for the soundtrack entity so we can find it later.
+/// Soundtracks fading out no longer have it.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 struct IsSoundtrack;
+
+/// Fades the [`ChannelVolume`] of a soundtrack, despawning it when faded out.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+struct SoundtrackFade {
+    from: f32,
+    to: f32,
+    timer: Timer,
+}
+
+impl SoundtrackFade {
+    fn new(from: f32, to: f32, seconds: f32) -> Self {
+        Self {
+            from,
+            to,
+            timer: Timer::from_seconds(seconds.max(0.0), TimerMode::Once),
+        }
+    }
+    fn out(from: f32, seconds: f32) -> Self {
+        Self::new(from, 0.0, seconds)
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+struct ActivePlaylist(Option<PlaylistState>);
+
+#[derive(Debug)]
+struct PlaylistState {
+    playlist: Playlist,
+    queue: Vec<SoundtrackKey>,
+    last: Option<SoundtrackKey>,
+    /// Counts down between tracks.
+    gap: Option<Timer>,
+}
+
+impl PlaylistState {
+    fn new(playlist: Playlist) -> Self {
+        Self {
+            playlist,
+            queue: Vec::new(),
+            last: None,
+            gap: None,
+        }
+    }
+    fn next_key(&mut self) -> Option<SoundtrackKey> {
+        if self.queue.is_empty() {
+            self.queue = self.playlist.tracks.iter().rev().copied().collect();
+            if self.playlist.order == PlaylistOrder::Shuffled {
+                self.queue.shuffle(&mut rand::thread_rng());
+                // don't repeat the last track when the shuffled playlist starts over
+                if self.queue.len() > 1 && self.queue.last() == self.last.as_ref() {
+                    self.queue.swap(0, self.queue.len() - 1);
+                }
+            }
+        }
+        let key = self.queue.pop()?;
+        self.last = Some(key);
+        Some(key)
+    }
+}
+
+fn fade_soundtracks(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut fade_query: Query<(Entity, &mut SoundtrackFade, &mut ChannelVolume)>,
+) {
+    for (entity, mut fade, mut channel_volume) in &mut fade_query {
+        fade.timer.tick(time.delta());
+        channel_volume.volume = fade.from + (fade.to - fade.from) * fade.timer.fraction();
+        if !fade.timer.finished() {
+            continue;
+        }
+        if fade.to <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            commands.entity(entity).remove::<SoundtrackFade>();
+        }
+    }
+}
+
+fn advance_playlist(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    soundtrack_query: Query<(Entity, Option<&AudioSink>), With<IsSoundtrack>>,
+    mut active_playlist: ResMut<ActivePlaylist>,
+    mixer: AudioMixer,
+) {
+    let Some(state) = active_playlist.0.as_mut() else {
+        return;
+    };
+    if let Some(gap) = state.gap.as_mut() {
+        if !gap.tick(time.delta()).finished() {
+            return;
+        }
+        state.gap = None;
+        let Some(soundtrack_key) = state.next_key() else {
+            return;
+        };
+        spawn_soundtrack(
+            &mut commands,
+            &soundtrack_handles,
+            &mixer,
+            soundtrack_key,
+            PlaybackMode::Once,
+            0.0,
+        );
+        return;
+    }
+    for (entity, sink) in &soundtrack_query {
+        // the sink is only added once the track started playing
+        if !sink.is_some_and(AudioSink::empty) {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        state.gap = Some(Timer::new(
+            Duration::from_secs_f32(state.playlist.gap.max(0.0)),
+            TimerMode::Once,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playlist_order() {
+        let tracks = [SoundtrackKey::Gameplay, SoundtrackKey::Credits];
+        let mut state = PlaylistState::new(Playlist::new(tracks));
+        let played = (0..4).map_while(|_| state.next_key()).collect::<Vec<_>>();
+        assert_eq!(played, [tracks, tracks].concat());
+
+        // no track plays twice in a row, even when the playlist starts over
+        let mut state = PlaylistState::new(Playlist::new(tracks).shuffled());
+        let mut previous = None;
+        for _ in 0..20 {
+            let key = state.next_key();
+            assert_ne!(key, previous);
+            previous = key;
+        }
+    }
+}
@@ -8,6 +8,9 @@ use transform_gizmo_bevy::GizmoCamera;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
+use crate::game::audio::sfx::LISTENER_EAR_GAP;
+use crate::game::input::{Action, ActionState};
+use crate::game::shake::ScreenShake;
 use crate::util::ref_ext::RefExt;
 
 pub(crate) fn plugin(app: &mut App) {
@@ -17,9 +20,16 @@ pub(crate) fn plugin(app: &mut App) {
     app.add_systems(
         PostUpdate,
         camera_follow
+            .in_set(CameraFollowSet)
             .after(sync_simple_transforms)
             .after(propagate_transforms),
     );
+    app.add_systems(
+        PostUpdate,
+        sync_camera_global_transforms
+            .in_set(CameraSyncSet)
+            .after(CameraFollowSet),
+    );
     app.add_systems(
         Update,
         (
@@ -36,6 +46,15 @@ pub(crate) fn plugin(app: &mut App) {
 #[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct MainCameraControllerSet;
 
+/// Moves [`FollowingCamera`]s onto their targets in `PostUpdate`.
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CameraFollowSet;
+
+/// Copies camera [`Transform`]s changed after propagation into their [`GlobalTransform`]s so the
+/// follow and anything ordered before this set is drawn the same frame.
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CameraSyncSet;
+
 #[derive(Event, Debug, Copy, Clone)]
 pub enum UnlockCamera {
     MainCamera,
@@ -73,9 +92,56 @@ pub struct PanningCamera;
 #[reflect(Component)]
 pub struct ZoomingCamera;
 
-#[derive(Component, Debug, Default, Reflect, AutoRegisterType)]
+/// How a [`FollowingCamera`] moves towards its targets.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect, AutoRegisterType)]
+pub enum FollowMode {
+    /// Jumps straight to the targets every frame.
+    #[default]
+    Snap,
+    /// Critically damped, never overshoots.
+    Smooth,
+}
+
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
 #[reflect(Component)]
-pub struct FollowingCamera;
+pub struct FollowingCamera {
+    pub mode: FollowMode,
+    /// Roughly the time in seconds to catch up with the targets when smoothing.
+    #[default(0.3)]
+    pub smooth_time: f32,
+    /// Radius around the camera the targets can move in without moving it.
+    pub dead_zone: f32,
+    /// Seconds of target velocity to lead the targets by.
+    pub look_ahead: f32,
+    /// Upper bound of the look-ahead distance.
+    #[default(100.0)]
+    pub max_look_ahead: f32,
+}
+
+impl FollowingCamera {
+    pub fn smooth() -> Self {
+        Self {
+            mode: FollowMode::Smooth,
+            ..default()
+        }
+    }
+    pub fn with_dead_zone(mut self, dead_zone: f32) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+    pub fn with_look_ahead(mut self, look_ahead: f32) -> Self {
+        self.look_ahead = look_ahead;
+        self
+    }
+}
+
+/// Motion of a [`FollowingCamera`] carried between frames.
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+struct FollowingState {
+    velocity: Vec2,
+    last_target: Option<Vec2>,
+}
 
 #[derive(Component, Debug, Default, Reflect, AutoRegisterType)]
 #[reflect(Component)]
@@ -87,6 +153,7 @@ pub struct CameraBundle {
     zooming_camera: ZoomingCamera,
     following_camera: FollowingCamera,
     following_cache: FollowingCache,
+    following_state: FollowingState,
     #[default(Name::new("Camera"))]
     name: Name,
     camera_2d_bundle: Camera2dBundle,
@@ -99,6 +166,8 @@ pub struct MainCameraBundle {
     zooming_camera: ZoomingCamera,
     following_camera: FollowingCamera,
     following_cache: FollowingCache,
+    following_state: FollowingState,
+    screen_shake: ScreenShake,
     #[default(GizmoCamera)]
     gizmo_camera: GizmoCamera,
     #[default(Name::new("MainCamera"))]
@@ -106,6 +175,15 @@ pub struct MainCameraBundle {
     camera_2d_bundle: Camera2dBundle,
     #[default(IsDefaultUiCamera)]
     is_default_ui_camera: IsDefaultUiCamera,
+    #[default(SpatialListener::new(LISTENER_EAR_GAP))]
+    spatial_listener: SpatialListener,
+}
+
+impl MainCameraBundle {
+    pub fn with_following_camera(mut self, following_camera: FollowingCamera) -> Self {
+        self.following_camera = following_camera;
+        self
+    }
 }
 
 #[derive(RegisterTypeBinder)]
@@ -121,39 +199,26 @@ pub struct CameraPanQuery<'w> {
 
 fn camera_pan(
     time: Res<Time>,
-    input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState>,
     mut camera_q: Query<CameraPanQuery, With<PanningCamera>>,
     mut unlock_camera: EventWriter<UnlockCamera>,
 ) {
-    let direction = {
-        let mut direction = Vec2::ZERO;
-
-        if input.pressed(KeyCode::KeyW) {
-            direction.y += 1.0;
-        }
-        if input.pressed(KeyCode::KeyS) {
-            direction.y -= 1.0;
-        }
-        if input.pressed(KeyCode::KeyA) {
-            direction.x -= 1.0;
-        }
-        if input.pressed(KeyCode::KeyD) {
-            direction.x += 1.0;
-        }
-        // normalize will return NaN if we call on a 0 length vector
-        if direction == Vec2::ZERO {
-            // return early
-            return;
-        }
-        direction.normalize().extend(0.0)
-    };
+    let direction = action_state.direction(
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+    );
+    if direction == Vec2::ZERO {
+        return;
+    }
 
-    let speed = if input.pressed(KeyCode::ShiftLeft) {
+    let speed = if action_state.pressed(Action::CameraFast) {
         500.0
     } else {
         50.0
     };
-    let move_amount = direction * speed * time.delta_seconds();
+    let move_amount = direction.extend(0.0) * speed * time.delta_seconds();
 
     for mut item in camera_q.iter_mut() {
         item.transform.translation += move_amount * item.projection.scale;
@@ -163,12 +228,12 @@ fn camera_pan(
 
 fn camera_zoom(
     time: Res<Time>,
-    input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState>,
     mut event_scroll: EventReader<MouseWheel>,
     mut camera_q: Query<Mut<OrthographicProjection>, With<ZoomingCamera>>,
 ) {
     for event in event_scroll.read() {
-        let speed = if input.pressed(KeyCode::ShiftLeft) {
+        let speed = if action_state.pressed(Action::CameraFast) {
             500.0
         } else {
             100.0
@@ -205,12 +270,49 @@ pub struct FollowTargetCameraQuery<'w> {
     global_transform: &'w GlobalTransform,
 }
 
+/// Critically damped spring towards `target`, see Game Programming Gems 4, chapter 1.10.
+fn smooth_damp(
+    current: Vec2,
+    target: Vec2,
+    velocity: &mut Vec2,
+    smooth_time: f32,
+    delta_seconds: f32,
+) -> Vec2 {
+    let omega = 2.0 / smooth_time.max(0.0001);
+    let x = omega * delta_seconds;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * delta_seconds;
+    *velocity = (*velocity - omega * temp) * exp;
+    let next = target + (change + temp) * exp;
+    // prevent overshooting
+    if (target - current).dot(next - target) > 0.0 {
+        *velocity = Vec2::ZERO;
+        return target;
+    }
+    next
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+struct FollowingCameraQuery<'w> {
+    entity: Entity,
+    transform: Mut<'w, Transform>,
+    following_camera: &'w FollowingCamera,
+    following_cache: Ref<'w, FollowingCache>,
+    following_state: Option<Mut<'w, FollowingState>>,
+}
+
 fn camera_follow(
+    time: Res<Time>,
     follow_targets: Query<FollowTargetCameraQuery, Without<FollowingCamera>>,
-    mut camera_q: Query<(Entity, Mut<Transform>, &FollowingCache), With<FollowingCamera>>,
+    mut camera_q: Query<FollowingCameraQuery>,
 ) {
-    for (camera_entity, mut transform, following_cache) in camera_q.iter_mut() {
-        let follow_target_positions: Vec<Vec3> = following_cache
+    let delta_seconds = time.delta_seconds();
+    for mut camera in camera_q.iter_mut() {
+        let camera_entity = camera.entity;
+        let follow_target_positions: Vec<Vec3> = camera
+            .following_cache
             .0
             .iter()
             .filter_map(|&follow_target| {
@@ -232,13 +334,59 @@ fn camera_follow(
 
         let transform_count = follow_target_positions.len();
 
-        if transform_count > 0 {
-            let average_translation = follow_target_positions
-                .into_iter()
-                .fold(Vec3::ZERO, |sum, next| sum + next)
-                / transform_count as f32;
-            transform.translation = average_translation;
+        if transform_count == 0 {
+            if let Some(mut state) = camera.following_state {
+                *state = FollowingState::default();
+            }
+            continue;
         }
+        let average_translation = follow_target_positions
+            .into_iter()
+            .fold(Vec3::ZERO, |sum, next| sum + next)
+            / transform_count as f32;
+        let settings = *camera.following_camera;
+        let (FollowMode::Smooth, Some(mut state)) = (settings.mode, camera.following_state) else {
+            camera.transform.translation = average_translation;
+            continue;
+        };
+        if camera.following_cache.is_changed() {
+            // new targets, their jump is not a velocity
+            state.last_target = None;
+        }
+        let target = average_translation.truncate();
+        let target_velocity = match state.last_target {
+            Some(last_target) if delta_seconds > 0.0 => (target - last_target) / delta_seconds,
+            _ => Vec2::ZERO,
+        };
+        state.last_target = Some(target);
+        let goal = target
+            + (target_velocity * settings.look_ahead).clamp_length_max(settings.max_look_ahead);
+        let current = camera.transform.translation.truncate();
+        let offset = goal - current;
+        let goal = if offset.length() <= settings.dead_zone {
+            current
+        } else {
+            goal - offset.normalize() * settings.dead_zone
+        };
+        let next = smooth_damp(
+            current,
+            goal,
+            &mut state.velocity,
+            settings.smooth_time,
+            delta_seconds,
+        );
+        camera.transform.translation = next.extend(camera.transform.translation.z);
+    }
+}
+
+fn sync_camera_global_transforms(
+    mut camera_q: Query<
+        (&Transform, &mut GlobalTransform),
+        (With<Camera>, Without<Parent>, Changed<Transform>),
+    >,
+) {
+    for (transform, mut global_transform) in camera_q.iter_mut() {
+        *global_transform = GlobalTransform::from(*transform);
     }
 }
 
@@ -390,6 +538,7 @@ mod tests {
             InputPlugin,
             TransformPlugin,
             HierarchyPlugin,
+            crate::game::input::plugin,
             plugin,
         ));
         let test_scene = app.world_mut().run_system_once(|mut commands: Commands| {
@@ -408,6 +557,24 @@ mod tests {
         (app, test_scene)
     }
 
+    #[test]
+    fn test_smooth_damp_converges_without_overshoot() {
+        let target = Vec2::new(100.0, -50.0);
+        let mut current = Vec2::ZERO;
+        let mut velocity = Vec2::ZERO;
+        let mut last_distance = current.distance(target);
+        for _ in 0..120 {
+            current = smooth_damp(current, target, &mut velocity, 0.3, 1.0 / 60.0);
+            let distance = current.distance(target);
+            assert!(distance <= last_distance, "moved away from the target");
+            last_distance = distance;
+        }
+        assert!(
+            last_distance < 1.0,
+            "expected to catch up, {last_distance} left"
+        );
+    }
+
     #[test]
     fn test_focus_add() {
         let (mut app, test_scene) = setup();
@@ -0,0 +1,93 @@
+//! In-game heads-up display showing each platter's score, level and spin.
+
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::game_over::GameOver;
+use crate::game::platter::platter::Platter;
+use crate::game::platter::score::PlatterScore;
+use crate::game::platter::spin::PlatterSpin;
+use crate::game::versus::VersusPlayer;
+use crate::screen::Screen;
+use crate::ui::palette::LABEL_TEXT;
+use crate::ui::theme::ThemeColor;
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.add_systems(OnEnter(Screen::Playing), spawn_hud);
+    app.add_systems(Update, update_hud.run_if(in_state(Screen::Playing)));
+}
+
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct HudText;
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Hud"),
+        HudText,
+        ThemeColor::LabelText,
+        StateScoped(Screen::Playing),
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: LABEL_TEXT,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+    ));
+}
+
+fn update_hud(
+    platter_q: Query<
+        (
+            &PlatterScore,
+            &PlatterSpin,
+            Option<&VersusPlayer>,
+            Has<GameOver>,
+        ),
+        With<Platter>,
+    >,
+    mut hud_q: Query<&mut Text, With<HudText>>,
+) {
+    let mut platters = platter_q.iter().collect::<Vec<_>>();
+    platters.sort_by_key(|(_, _, player, _)| player.map(|player| player.index));
+    let mut value = String::new();
+    for (score, spin, player, is_game_over) in platters {
+        if let Some(player) = player {
+            let _ = writeln!(value, "Player {}", player.index + 1);
+        }
+        let _ = writeln!(
+            value,
+            "Score: {}\nLevel: {}\nSpin: {:+.2} rad/s",
+            score.points,
+            score.level(),
+            spin.base * spin.direction,
+        );
+        if is_game_over {
+            let _ = writeln!(value, "GAME OVER");
+        }
+    }
+    for mut text in hud_q.iter_mut() {
+        let Some(section) = text.sections.first_mut() else {
+            continue;
+        };
+        if section.value != value {
+            section.value.clone_from(&value);
+        }
+    }
+}
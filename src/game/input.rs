@@ -0,0 +1,356 @@
+//! Maps keys, mouse buttons and gamepad buttons to [`Action`]s, gameplay only ever reads actions.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<InputMap>();
+    app.init_resource::<ActionState>();
+    app.add_systems(PreUpdate, update_action_state.after(InputSystem));
+}
+
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    AutoRegisterType,
+)]
+pub enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    /// Held to pan and zoom the camera faster.
+    CameraFast,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    /// Spins the platter of the given player.
+    SpinLeft(usize),
+    SpinRight(usize),
+    Drop(usize),
+    /// Opens and closes the pause menu.
+    Pause,
+    /// Debug, spawns a bomb on every platter.
+    SpawnBomb,
+    /// Debug, spawns a locked block on every platter.
+    SpawnLocked,
+    /// Debug, raises a layer of garbage on every platter.
+    RaiseGarbage,
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize, AutoRegisterType,
+)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Matches the button on any connected gamepad.
+    Gamepad(GamepadButtonType),
+}
+
+impl From<KeyCode> for InputBinding {
+    fn from(value: KeyCode) -> Self {
+        Self::Key(value)
+    }
+}
+
+impl From<MouseButton> for InputBinding {
+    fn from(value: MouseButton) -> Self {
+        Self::Mouse(value)
+    }
+}
+
+impl From<GamepadButtonType> for InputBinding {
+    fn from(value: GamepadButtonType) -> Self {
+        Self::Gamepad(value)
+    }
+}
+
+/// Bindings of every action, an action is pressed while any of its bindings is.
+///
+/// Stored in the settings as a list sorted by action, so the file doesn't reorder on every save.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, AutoRegisterType)]
+#[reflect(Resource)]
+#[serde(
+    from = "Vec<(Action, Vec<InputBinding>)>",
+    into = "Vec<(Action, Vec<InputBinding>)>"
+)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<InputBinding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let mut input_map = Self::empty();
+        input_map
+            .bind(Action::PanUp, KeyCode::KeyW)
+            .bind(Action::PanDown, KeyCode::KeyS)
+            .bind(Action::PanLeft, KeyCode::KeyA)
+            .bind(Action::PanRight, KeyCode::KeyD)
+            .bind(Action::CameraFast, KeyCode::ShiftLeft)
+            // WASD pans the camera, so moving is left to the arrows and the D-pad
+            .bind(Action::MoveUp, KeyCode::ArrowUp)
+            .bind(Action::MoveUp, GamepadButtonType::DPadUp)
+            .bind(Action::MoveDown, KeyCode::ArrowDown)
+            .bind(Action::MoveDown, GamepadButtonType::DPadDown)
+            .bind(Action::MoveLeft, KeyCode::ArrowLeft)
+            .bind(Action::MoveLeft, GamepadButtonType::DPadLeft)
+            .bind(Action::MoveRight, KeyCode::ArrowRight)
+            .bind(Action::MoveRight, GamepadButtonType::DPadRight)
+            .bind(Action::SpinLeft(0), KeyCode::KeyQ)
+            .bind(Action::SpinLeft(0), GamepadButtonType::LeftTrigger)
+            .bind(Action::SpinRight(0), KeyCode::KeyE)
+            .bind(Action::SpinRight(0), GamepadButtonType::RightTrigger)
+            .bind(Action::Drop(0), KeyCode::Space)
+            .bind(Action::Drop(0), GamepadButtonType::South)
+            .bind(Action::SpinLeft(1), KeyCode::KeyJ)
+            .bind(Action::SpinRight(1), KeyCode::KeyK)
+            .bind(Action::Drop(1), KeyCode::Enter)
+            .bind(Action::Pause, KeyCode::Escape)
+            .bind(Action::Pause, GamepadButtonType::Start)
+            .bind(Action::SpawnBomb, KeyCode::KeyB)
+            .bind(Action::SpawnLocked, KeyCode::KeyL)
+            .bind(Action::RaiseGarbage, KeyCode::KeyG);
+        input_map
+    }
+}
+
+impl InputMap {
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::default(),
+        }
+    }
+    pub fn bind(&mut self, action: Action, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+    pub fn unbind(&mut self, action: Action, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        if let Some(bindings) = self.bindings.get_mut(&action) {
+            bindings.retain(|&bound| bound != binding);
+        }
+        self
+    }
+    pub fn clear(&mut self, action: Action) -> &mut Self {
+        self.bindings.remove(&action);
+        self
+    }
+    pub fn bindings(&self, action: Action) -> &[InputBinding] {
+        self.bindings
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+impl From<Vec<(Action, Vec<InputBinding>)>> for InputMap {
+    fn from(value: Vec<(Action, Vec<InputBinding>)>) -> Self {
+        let mut input_map = Self::empty();
+        for (action, bindings) in value {
+            for binding in bindings {
+                input_map.bind(action, binding);
+            }
+        }
+        input_map
+    }
+}
+
+impl From<InputMap> for Vec<(Action, Vec<InputBinding>)> {
+    fn from(value: InputMap) -> Self {
+        value
+            .bindings
+            .into_iter()
+            .filter(|(_, bindings)| !bindings.is_empty())
+            .sorted_by_key(|&(action, _)| action)
+            .collect()
+    }
+}
+
+/// Actions pressed this frame, updated in `PreUpdate` from [`InputMap`].
+#[derive(Resource, Debug, Default, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    just_released: HashSet<Action>,
+    /// Held without any device, for tests and scripted input.
+    synthetic: HashSet<Action>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+    /// Normalized direction from four actions, zero when nothing or opposing actions are pressed.
+    pub fn direction(&self, up: Action, down: Action, left: Action, right: Action) -> Vec2 {
+        let axis = |negative, positive| {
+            (self.pressed(positive) as i8 - self.pressed(negative) as i8) as f32
+        };
+        Vec2::new(axis(left, right), axis(down, up)).normalize_or_zero()
+    }
+    /// Holds `action` until [`ActionState::release`], takes effect on the next update.
+    pub fn press(&mut self, action: Action) {
+        self.synthetic.insert(action);
+    }
+    pub fn release(&mut self, action: Action) {
+        self.synthetic.remove(&action);
+    }
+    fn update(&mut self, pressed: HashSet<Action>) {
+        self.just_pressed = pressed.difference(&self.pressed).copied().collect();
+        self.just_released = self.pressed.difference(&pressed).copied().collect();
+        self.pressed = pressed;
+    }
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn update_action_state(
+    input_map: Res<InputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let is_pressed = |binding: &InputBinding| match *binding {
+        InputBinding::Key(key) => keys.pressed(key),
+        InputBinding::Mouse(button) => mouse_buttons.pressed(button),
+        InputBinding::Gamepad(button_type) => gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.pressed(GamepadButton::new(gamepad, button_type))),
+    };
+    let pressed = input_map
+        .bindings
+        .iter()
+        .filter(|(_, bindings)| bindings.iter().any(&is_pressed))
+        .map(|(&action, _)| action)
+        .chain(action_state.synthetic.iter().copied())
+        .collect::<HashSet<_>>();
+    action_state.update(pressed);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::InputPlugin;
+
+    use super::*;
+
+    fn setup() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, plugin));
+        app.update();
+        app
+    }
+
+    #[test]
+    fn test_key_maps_to_action() {
+        let mut app = setup();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyQ);
+        app.update();
+        let action_state = app.world().resource::<ActionState>();
+        assert!(action_state.pressed(Action::SpinLeft(0)));
+        assert!(action_state.just_pressed(Action::SpinLeft(0)));
+        assert!(!action_state.pressed(Action::SpinLeft(1)));
+        app.update();
+        let action_state = app.world().resource::<ActionState>();
+        assert!(action_state.pressed(Action::SpinLeft(0)));
+        assert!(!action_state.just_pressed(Action::SpinLeft(0)));
+    }
+
+    #[test]
+    fn test_synthetic_press_and_release() {
+        let mut app = setup();
+        app.world_mut()
+            .resource_mut::<ActionState>()
+            .press(Action::PanUp);
+        app.update();
+        let action_state = app.world().resource::<ActionState>();
+        assert_eq!(
+            action_state.direction(
+                Action::PanUp,
+                Action::PanDown,
+                Action::PanLeft,
+                Action::PanRight
+            ),
+            Vec2::Y
+        );
+        app.world_mut()
+            .resource_mut::<ActionState>()
+            .release(Action::PanUp);
+        app.update();
+        assert!(app
+            .world()
+            .resource::<ActionState>()
+            .just_released(Action::PanUp));
+    }
+
+    #[test]
+    fn test_default_bindings_are_not_shared() {
+        // every action is read while playing, a shared binding would trigger both
+        let input_map = InputMap::default();
+        let mut bound = HashMap::<InputBinding, Action>::default();
+        for (&action, bindings) in input_map.bindings.iter() {
+            for &binding in bindings {
+                if let Some(other) = bound.insert(binding, action) {
+                    panic!("{binding:?} is bound to both {other:?} and {action:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rebinding() {
+        let mut app = setup();
+        app.world_mut()
+            .resource_mut::<InputMap>()
+            .clear(Action::Drop(0))
+            .bind(Action::Drop(0), MouseButton::Left);
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Space);
+        app.update();
+        assert!(!app
+            .world()
+            .resource::<ActionState>()
+            .pressed(Action::Drop(0)));
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        assert!(app
+            .world()
+            .resource::<ActionState>()
+            .pressed(Action::Drop(0)));
+    }
+}
@@ -6,20 +6,28 @@ mod animation;
 pub mod assets;
 pub mod audio;
 pub mod camera;
+pub mod hud;
+pub mod input;
 mod movement;
 pub mod platter;
+pub mod shake;
 pub mod spawn;
 pub mod util;
+pub mod versus;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         util::plugin,
+        input::plugin,
         camera::plugin,
+        shake::plugin,
         animation::plugin,
         audio::plugin,
         assets::plugin,
         movement::plugin,
         spawn::plugin,
         platter::plugin,
+        versus::plugin,
+        hud::plugin,
     ));
 }
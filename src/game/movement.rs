@@ -5,7 +5,10 @@
 
 use bevy::{prelude::*, window::PrimaryWindow};
 
-use crate::AppSet;
+use crate::{
+    game::input::{Action, ActionState},
+    AppSet,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Record directional input as movement controls.
@@ -30,27 +33,17 @@ pub(super) fn plugin(app: &mut App) {
 pub struct MovementController(pub Vec2);
 
 fn record_movement_controller(
-    input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState>,
     mut controller_query: Query<&mut MovementController>,
 ) {
-    // Collect directional input.
-    let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
-        intent.y += 1.0;
-    }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-        intent.y -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
-        intent.x -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
-        intent.x += 1.0;
-    }
-
-    // Normalize so that diagonal movement has the same speed as
+    // Collect directional input, normalized so that diagonal movement has the same speed as
     // horizontal and vertical movement.
-    let intent = intent.normalize_or_zero();
+    let intent = action_state.direction(
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+    );
 
     // Apply movement intent to controllers.
     for mut controller in &mut controller_query {
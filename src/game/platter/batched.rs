@@ -0,0 +1,164 @@
+use std::ops::Range;
+
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::mesh::{PlatterMeshOptions, PlatterSegmentMesh};
+use crate::game::platter::platter::Platter;
+use crate::game::platter::segment::{PlatterSegment, PlatterSegmentColor};
+use crate::game::util::mesh::annular_sector_triangulate;
+use crate::util::PrototypeManagerSystemParam;
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.add_systems(
+        PostUpdate,
+        (build_batched_mesh, update_batched_colors).chain(),
+    );
+}
+
+/// How the segments of a platter are drawn.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect, AutoRegisterType)]
+pub enum PlatterRenderMode {
+    /// Every segment draws its own mesh and material.
+    #[default]
+    PerSegment,
+    /// The whole platter is drawn as a single vertex coloured mesh, segments stay as logical entities.
+    Batched,
+}
+
+/// Segment whose colour is drawn by its platter's [`PlatterBatchedMesh`].
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct BatchedPlatterSegment;
+
+/// The single mesh drawing all segments of a batched platter.
+#[derive(Component, Debug, Default, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct PlatterBatchedMesh {
+    pub mesh: Handle<Mesh>,
+    /// Vertices belonging to each segment, used to recolour only what changed.
+    #[reflect(ignore)]
+    segment_vertices: EntityHashMap<Range<usize>>,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn batched_mesh(
+    segments: &[(Entity, &PlatterSegmentMesh, Color)],
+) -> (Mesh, EntityHashMap<Range<usize>>) {
+    let mut positions = vec![];
+    let mut uvs = vec![];
+    let mut colors = vec![];
+    let mut indices = vec![];
+    let mut segment_vertices = EntityHashMap::default();
+    for &(entity, psm, color) in segments {
+        let start = positions.len();
+        let options = psm.options;
+        indices.extend(
+            annular_sector_triangulate(options.inner_resolution, options.outer_resolution)
+                .into_flattened()
+                .into_iter()
+                .map(|ix| (start + ix) as u32),
+        );
+        positions.extend(psm.vertices.iter().map(|v| v.extend(0.0)));
+        uvs.extend(psm.uvs());
+        colors.extend(vec![color.to_linear().to_f32_array(); psm.vertices.len()]);
+        segment_vertices.insert(entity, start..positions.len());
+    }
+    let normals = vec![Vec3::Z; positions.len()];
+    // kept in the main world so colours can be updated in place
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_indices(Indices::U32(indices));
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    (mesh, segment_vertices)
+}
+
+fn build_batched_mesh(
+    mut commands: Commands,
+    mut prototype_manager_system_param: PrototypeManagerSystemParam,
+    platter_q: Query<(Entity, &PlatterMeshOptions, &Children), Added<Platter>>,
+    segments_q: Query<(Entity, &PlatterSegmentMesh, &PlatterSegmentColor), With<PlatterSegment>>,
+) {
+    for (platter, pmo, children) in platter_q.iter() {
+        if pmo.get().render_mode != PlatterRenderMode::Batched {
+            continue;
+        }
+        let segments = children
+            .iter()
+            .filter_map(|&child| segments_q.get(child).ok())
+            .map(|(entity, psm, psc)| (entity, psm, psc.get()))
+            .collect::<Vec<_>>();
+        for &(entity, _, _) in &segments {
+            commands
+                .entity(entity)
+                .insert((BatchedPlatterSegment, Visibility::Hidden));
+        }
+        let (mesh, segment_vertices) = batched_mesh(&segments);
+        let mesh = prototype_manager_system_param.meshes.add(mesh);
+        commands.entity(platter).with_children(|parent| {
+            parent.spawn((
+                Name::new("PlatterBatchedMesh"),
+                PlatterBatchedMesh {
+                    mesh: mesh.clone(),
+                    segment_vertices,
+                },
+                ColorMesh2dBundle {
+                    mesh: mesh.into(),
+                    // vertex colours are multiplied with the material colour
+                    material: prototype_manager_system_param.get_or_create_material(Color::WHITE),
+                    transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                    ..default()
+                },
+            ));
+        });
+    }
+}
+
+fn update_batched_colors(
+    mut meshes: ResMut<Assets<Mesh>>,
+    batched_q: Query<(&Parent, &PlatterBatchedMesh)>,
+    segments_q: Query<
+        (Entity, &Parent, &PlatterSegmentColor),
+        (With<BatchedPlatterSegment>, Changed<PlatterSegmentColor>),
+    >,
+) {
+    if segments_q.is_empty() {
+        return;
+    }
+    for (platter, batched) in batched_q.iter() {
+        let mut changed = segments_q
+            .iter()
+            .filter(|(_, parent, _)| parent.get() == platter.get())
+            .peekable();
+        if changed.peek().is_none() {
+            continue;
+        }
+        let Some(mesh) = meshes.get_mut(&batched.mesh) else {
+            continue;
+        };
+        let Some(VertexAttributeValues::Float32x4(colors)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+        else {
+            panic!("batched platter mesh is missing vertex colors");
+        };
+        for (entity, _, psc) in changed {
+            let Some(range) = batched.segment_vertices.get(&entity) else {
+                continue;
+            };
+            colors[range.clone()].fill(psc.get().to_linear().to_f32_array());
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use bevy::ecs::entity::{EntityHashMap, EntityHashSet};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use rand::Rng;
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::falling::{BlockLanded, FallingBlock, FallingSystemSet};
+use crate::game::platter::index::{
+    PlatterSegmentIndex, PolarDimensions, PolarIndex, PolarPosition,
+};
+use crate::game::platter::mesh::PlatterSegmentMesh;
+use crate::game::platter::platter::Platter;
+use crate::game::platter::score::PlatterScore;
+use crate::game::platter::segment::PlatterSegment;
+use crate::game::platter::value::{CellValue, PlatterSegmentValue};
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.add_event::<SegmentsCleared>();
+    app.add_event::<RaiseGarbage>();
+    app.add_event::<GarbageOverflow>();
+    app.configure_sets(Update, ClearSystemSet.after(FallingSystemSet));
+    app.add_systems(
+        Update,
+        (raise_garbage, resolve_clears)
+            .chain()
+            .in_set(ClearSystemSet),
+    );
+}
+
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ClearSystemSet;
+
+/// Rule set used to decide which settled segments get cleared.
+#[derive(Component, Debug, SmartDefault, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub enum ClearMode {
+    /// Clears every onion layer that is filled all the way around.
+    #[default]
+    Rings,
+    /// Clears groups of at least `min_group` connected segments sharing the same value.
+    Match { min_group: usize },
+}
+
+/// Sent for every step of a clear cascade, `chain` starts at 0 for the initial clear.
+#[derive(Event, Debug, Clone)]
+pub struct SegmentsCleared {
+    pub platter: Entity,
+    pub segments: Vec<Entity>,
+    pub rings: usize,
+    pub chain: usize,
+}
+
+/// Pushes every settled segment outward and fills the inner layers with garbage.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct RaiseGarbage {
+    pub platter: Entity,
+    pub layers: usize,
+}
+
+/// Sent when raising garbage pushed settled segments off the outside of the platter.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct GarbageOverflow {
+    pub platter: Entity,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+/// Settled values indexed by `[pie_cut][onion_layer]`.
+pub type ValueGrid = Vec<Vec<Option<CellValue>>>;
+
+/// Cells taken by a [`FallingBlock`], indexed like [`ValueGrid`].
+pub type FallingGrid = Vec<Vec<bool>>;
+
+type Position = PolarPosition;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClearStep {
+    pub cleared: Vec<Position>,
+    pub unlocked: Vec<Position>,
+    pub rings: usize,
+    /// Matched groups, always 0 when clearing rings.
+    pub groups: usize,
+}
+
+/// Positions adjacent to `(pie_cut, onion_layer)`, wrapping around the pie cuts.
+pub fn polar_neighbours(
+    pie_cuts: usize,
+    onion_layers: usize,
+    pos: Position,
+) -> impl Iterator<Item = Position> {
+    PolarDimensions::new(pie_cuts, onion_layers).neighbours(pos)
+}
+
+/// [`polar_neighbours`] including the diagonals.
+fn blast_neighbours(pie_cuts: usize, onion_layers: usize, pos: Position) -> HashSet<Position> {
+    let mut blast = HashSet::default();
+    for neighbour in polar_neighbours(pie_cuts, onion_layers, pos) {
+        blast.insert(neighbour);
+        if neighbour.1 == pos.1 {
+            blast.extend(polar_neighbours(pie_cuts, onion_layers, neighbour));
+        }
+    }
+    blast.remove(&pos);
+    blast
+}
+
+fn grid_size(grid: &ValueGrid) -> (usize, usize) {
+    (grid.len(), grid.first().map_or(0, Vec::len))
+}
+
+pub fn find_ring_clears(grid: &ValueGrid) -> Vec<Position> {
+    let (pie_cuts, onion_layers) = grid_size(grid);
+    (0..onion_layers)
+        .filter(|&layer| {
+            grid.iter().all(|pie_cut| {
+                matches!(pie_cut[layer], Some(value) if !matches!(value, CellValue::Locked(_)))
+            })
+        })
+        .flat_map(|layer| (0..pie_cuts).map(move |pie_cut| (pie_cut, layer)))
+        .collect()
+}
+
+pub fn find_match_groups(grid: &ValueGrid, min_group: usize) -> Vec<Vec<Position>> {
+    let (pie_cuts, onion_layers) = grid_size(grid);
+    let mut visited = vec![vec![false; onion_layers]; pie_cuts];
+    let mut groups = vec![];
+    for pie_cut in 0..pie_cuts {
+        for onion_layer in 0..onion_layers {
+            if visited[pie_cut][onion_layer] {
+                continue;
+            }
+            visited[pie_cut][onion_layer] = true;
+            // only plain blocks take part in matching
+            let Some(value @ CellValue::Block(_)) = grid[pie_cut][onion_layer] else {
+                continue;
+            };
+            let mut group = vec![(pie_cut, onion_layer)];
+            let mut stack = vec![(pie_cut, onion_layer)];
+            while let Some(pos) = stack.pop() {
+                for (next_pie_cut, next_layer) in polar_neighbours(pie_cuts, onion_layers, pos) {
+                    if visited[next_pie_cut][next_layer]
+                        || grid[next_pie_cut][next_layer] != Some(value)
+                    {
+                        continue;
+                    }
+                    visited[next_pie_cut][next_layer] = true;
+                    group.push((next_pie_cut, next_layer));
+                    stack.push((next_pie_cut, next_layer));
+                }
+            }
+            if group.len() >= min_group {
+                groups.push(group);
+            }
+        }
+    }
+    groups
+}
+
+/// Expands the rule based clears with bombs, garbage and locked segments.
+pub fn find_clears(grid: &ValueGrid, mode: ClearMode) -> ClearStep {
+    let (pie_cuts, onion_layers) = grid_size(grid);
+    let (cleared, groups) = match mode {
+        ClearMode::Rings => (find_ring_clears(grid), 0),
+        ClearMode::Match { min_group } => {
+            let groups = find_match_groups(grid, min_group.max(1));
+            let count = groups.len();
+            (groups.into_iter().flatten().collect(), count)
+        }
+    };
+    let mut cleared = cleared.into_iter().collect::<HashSet<_>>();
+
+    // bombs only ever stay settled for a single resolve so any bomb in the grid just landed
+    for (pie_cut, layers) in grid.iter().enumerate() {
+        for (onion_layer, value) in layers.iter().enumerate() {
+            if *value == Some(CellValue::Bomb) {
+                cleared.insert((pie_cut, onion_layer));
+            }
+        }
+    }
+    let mut stack = cleared.iter().copied().collect::<Vec<_>>();
+    while let Some(pos) = stack.pop() {
+        if grid[pos.0][pos.1] != Some(CellValue::Bomb) {
+            continue;
+        }
+        for neighbour in blast_neighbours(pie_cuts, onion_layers, pos) {
+            if grid[neighbour.0][neighbour.1].is_some() && cleared.insert(neighbour) {
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    let touched = cleared
+        .iter()
+        .flat_map(|&pos| polar_neighbours(pie_cuts, onion_layers, pos))
+        .collect::<HashSet<_>>();
+    if let ClearMode::Match { .. } = mode {
+        for &pos in touched.iter() {
+            if grid[pos.0][pos.1] == Some(CellValue::Garbage) {
+                cleared.insert(pos);
+            }
+        }
+    }
+
+    let is_locked = |pos: &Position| matches!(grid[pos.0][pos.1], Some(CellValue::Locked(_)));
+    let mut unlocked = cleared
+        .union(&touched)
+        .copied()
+        .filter(|pos| is_locked(pos))
+        .collect::<Vec<_>>();
+    unlocked.sort_unstable();
+    let mut cleared = cleared
+        .into_iter()
+        .filter(|pos| !is_locked(pos))
+        .collect::<Vec<_>>();
+    cleared.sort_unstable();
+
+    let rings = match mode {
+        ClearMode::Rings => cleared_rings(pie_cuts, onion_layers, &cleared).count(),
+        ClearMode::Match { .. } => 0,
+    };
+    ClearStep {
+        cleared,
+        unlocked,
+        rings,
+        groups,
+    }
+}
+
+fn cleared_rings(
+    pie_cuts: usize,
+    onion_layers: usize,
+    cleared: &[Position],
+) -> impl DoubleEndedIterator<Item = usize> + '_ {
+    (0..onion_layers)
+        .filter(move |&layer| (0..pie_cuts).all(|pie_cut| cleared.contains(&(pie_cut, layer))))
+}
+
+/// Applies a [`ClearStep`] and lets everything outside of it fall inward.
+pub fn apply_gravity(grid: &mut ValueGrid, mode: ClearMode, step: &ClearStep) {
+    let (pie_cuts, onion_layers) = grid_size(grid);
+    for &(pie_cut, onion_layer) in step.cleared.iter() {
+        grid[pie_cut][onion_layer] = None;
+    }
+    for &(pie_cut, onion_layer) in step.unlocked.iter() {
+        if let Some(CellValue::Locked(inner)) = grid[pie_cut][onion_layer] {
+            grid[pie_cut][onion_layer] = Some(CellValue::Block(inner));
+        }
+    }
+    match mode {
+        ClearMode::Rings => {
+            // remove from the outside in so the remaining indices stay valid
+            for layer in cleared_rings(pie_cuts, onion_layers, &step.cleared).rev() {
+                for pie_cut in grid.iter_mut() {
+                    pie_cut.remove(layer);
+                    pie_cut.push(None);
+                }
+            }
+        }
+        ClearMode::Match { .. } => {
+            for pie_cut in grid.iter_mut() {
+                pie_cut.retain(Option::is_some);
+                pie_cut.resize(onion_layers, None);
+            }
+        }
+    }
+}
+
+/// Repeatedly clears and applies gravity until the grid is stable, returning each step.
+pub fn resolve(grid: &mut ValueGrid, mode: ClearMode) -> Vec<ClearStep> {
+    let mut steps = vec![];
+    loop {
+        let step = find_clears(grid, mode);
+        if step.cleared.is_empty() {
+            break;
+        }
+        apply_gravity(grid, mode, &step);
+        steps.push(step);
+    }
+    steps
+}
+
+/// Inserts `layers` rings of garbage at the centre, leaving a gap at `gap_pie_cut`.
+/// Falling cells count as occupied and move outward with their column in `falling`.
+/// Returns `true` if any value was pushed off the outside.
+pub fn push_garbage(
+    grid: &mut ValueGrid,
+    falling: &mut FallingGrid,
+    layers: usize,
+    gap_pie_cut: usize,
+) -> bool {
+    let mut overflowed = false;
+    for (pie_cut, (column, falling_column)) in grid.iter_mut().zip(falling.iter_mut()).enumerate() {
+        for _ in 0..layers {
+            overflowed |= column.pop().flatten().is_some();
+            falling_column.pop();
+            let value = (pie_cut != gap_pie_cut).then_some(CellValue::Garbage);
+            column.insert(0, value);
+            falling_column.insert(0, false);
+        }
+    }
+    overflowed
+}
+
+type SettledSegmentsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static PlatterSegmentMesh,
+        &'static mut PlatterSegmentValue,
+    ),
+    (With<PlatterSegment>, Without<FallingBlock>),
+>;
+
+type SegmentEntities = Vec<Vec<Option<Entity>>>;
+
+fn read_grid(
+    polar_index: &PolarIndex,
+    segments_q: &SettledSegmentsQuery,
+) -> (SegmentEntities, ValueGrid) {
+    let PolarDimensions {
+        pie_cuts,
+        onion_layers,
+    } = polar_index.dimensions();
+    let mut entities = vec![vec![None; onion_layers]; pie_cuts];
+    let mut grid: ValueGrid = vec![vec![None; onion_layers]; pie_cuts];
+    for ((pie_cut, onion_layer), segment) in polar_index.iter() {
+        let Some((entity, _, psv)) = segments_q.get(segment).ok() else {
+            continue;
+        };
+        entities[pie_cut][onion_layer] = Some(entity);
+        grid[pie_cut][onion_layer] = psv.0;
+    }
+    (entities, grid)
+}
+
+fn write_grid(entities: &SegmentEntities, grid: ValueGrid, segments_q: &mut SettledSegmentsQuery) {
+    for (pie_cut, layers) in grid.into_iter().enumerate() {
+        for (onion_layer, value) in layers.into_iter().enumerate() {
+            let Some(entity) = entities[pie_cut][onion_layer] else {
+                continue;
+            };
+            let Some((_, _, mut psv)) = segments_q.get_mut(entity).ok() else {
+                continue;
+            };
+            if psv.0 != value {
+                psv.0 = value;
+            }
+        }
+    }
+}
+
+fn raise_garbage(
+    mut commands: Commands,
+    mut raise_garbage: EventReader<RaiseGarbage>,
+    index: Res<PlatterSegmentIndex>,
+    mut segments_q: Query<(&mut PlatterSegmentValue, Has<FallingBlock>), With<PlatterSegment>>,
+    mut garbage_overflow: EventWriter<GarbageOverflow>,
+) {
+    // merged so a second raise can't miss the falling markers moved by the first
+    let mut layers_by_platter = EntityHashMap::<usize>::default();
+    for &event in raise_garbage.read() {
+        *layers_by_platter.entry(event.platter).or_default() += event.layers;
+    }
+    for (platter, layers) in layers_by_platter {
+        let Some(polar_index) = index.get(platter) else {
+            continue;
+        };
+        let PolarDimensions {
+            pie_cuts,
+            onion_layers,
+        } = polar_index.dimensions();
+        let mut grid: ValueGrid = vec![vec![None; onion_layers]; pie_cuts];
+        let mut falling: FallingGrid = vec![vec![false; onion_layers]; pie_cuts];
+        for ((pie_cut, onion_layer), segment) in polar_index.iter() {
+            let Some((psv, is_falling)) = segments_q.get(segment).ok() else {
+                continue;
+            };
+            grid[pie_cut][onion_layer] = psv.0;
+            falling[pie_cut][onion_layer] = is_falling;
+        }
+        let gap_pie_cut = rand::thread_rng().gen_range(0..pie_cuts.max(1));
+        if push_garbage(&mut grid, &mut falling, layers, gap_pie_cut) {
+            garbage_overflow.send(GarbageOverflow { platter });
+        }
+        for ((pie_cut, onion_layer), segment) in polar_index.iter() {
+            let Some((mut psv, was_falling)) = segments_q.get_mut(segment).ok() else {
+                continue;
+            };
+            let value = grid[pie_cut][onion_layer];
+            if psv.0 != value {
+                psv.0 = value;
+            }
+            let is_falling = falling[pie_cut][onion_layer];
+            if is_falling && !was_falling {
+                commands.entity(segment).insert(FallingBlock);
+            } else if was_falling && !is_falling {
+                commands.entity(segment).remove::<FallingBlock>();
+            }
+        }
+    }
+}
+
+fn resolve_clears(
+    mut block_landed: EventReader<BlockLanded>,
+    index: Res<PlatterSegmentIndex>,
+    mut platter_q: Query<(&ClearMode, Mut<PlatterScore>), With<Platter>>,
+    mut segments_q: SettledSegmentsQuery,
+    mut segments_cleared: EventWriter<SegmentsCleared>,
+) {
+    let platters = block_landed
+        .read()
+        .map(|event| event.platter)
+        .collect::<EntityHashSet>();
+    for platter in platters {
+        let Some((&mode, mut score)) = platter_q.get_mut(platter).ok() else {
+            continue;
+        };
+        let Some(polar_index) = index.get(platter) else {
+            continue;
+        };
+        let (entities, mut grid) = read_grid(polar_index, &segments_q);
+
+        let steps = resolve(&mut grid, mode);
+        if steps.is_empty() {
+            continue;
+        }
+
+        for (chain, step) in steps.into_iter().enumerate() {
+            score.add_clear(step.cleared.len(), step.rings, step.groups, chain);
+            segments_cleared.send(SegmentsCleared {
+                platter,
+                segments: step
+                    .cleared
+                    .iter()
+                    .filter_map(|&(pie_cut, onion_layer)| entities[pie_cut][onion_layer])
+                    .collect(),
+                rings: step.rings,
+                chain,
+            });
+        }
+
+        write_grid(&entities, grid, &mut segments_q);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::platter::value::InnerValue;
+
+    use super::*;
+
+    const R: Option<CellValue> = Some(CellValue::Block(InnerValue::RedZ));
+    const G: Option<CellValue> = Some(CellValue::Block(InnerValue::GreenS));
+    const L: Option<CellValue> = Some(CellValue::Locked(InnerValue::RedZ));
+    const B: Option<CellValue> = Some(CellValue::Bomb);
+    const X: Option<CellValue> = Some(CellValue::Garbage);
+    const N: Option<CellValue> = None;
+
+    #[test]
+    fn test_polar_neighbours_wrap() {
+        let mut neighbours = polar_neighbours(4, 3, (0, 0)).collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(neighbours, vec![(0, 1), (1, 0), (3, 0)]);
+        let mut neighbours = polar_neighbours(2, 3, (1, 1)).collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(neighbours, vec![(0, 1), (1, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn test_ring_clear_shifts_outer_rings_inward() {
+        let mut grid = vec![vec![R, G, N], vec![G, G, N], vec![R, N, N]];
+        let steps = resolve(&mut grid, ClearMode::Rings);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].rings, 1);
+        assert_eq!(steps[0].groups, 0);
+        assert_eq!(grid, vec![vec![G, N, N], vec![G, N, N], vec![N, N, N]]);
+    }
+
+    #[test]
+    fn test_match_wraps_around_pie_cuts() {
+        let mut grid = vec![vec![R, N], vec![G, N], vec![G, N], vec![R, N]];
+        let steps = resolve(&mut grid, ClearMode::Match { min_group: 2 });
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].cleared.len(), 4);
+        assert_eq!(steps[0].groups, 2);
+        assert!(grid.iter().flatten().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_match_chain() {
+        // clearing the greens lets the outer red fall next to the inner reds
+        let mut grid = vec![vec![R, G, R], vec![R, G, N], vec![N, G, N], vec![N, N, N]];
+        let steps = resolve(&mut grid, ClearMode::Match { min_group: 3 });
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].cleared.len(), 3);
+        assert_eq!(steps[1].cleared.len(), 3);
+        assert!(grid.iter().flatten().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_bomb_clears_neighbourhood() {
+        let mut grid = vec![
+            vec![R, G, N],
+            vec![G, B, N],
+            vec![R, R, G],
+            vec![G, N, N],
+            vec![G, N, N],
+        ];
+        let steps = resolve(&mut grid, ClearMode::Match { min_group: 4 });
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].cleared.len(), 7);
+        assert_eq!(
+            grid,
+            vec![
+                vec![N, N, N],
+                vec![N, N, N],
+                vec![N, N, N],
+                vec![G, N, N],
+                vec![G, N, N],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locked_unlocks_instead_of_clearing() {
+        let mut grid = vec![vec![R, L], vec![R, N], vec![R, N], vec![G, N]];
+        let steps = resolve(&mut grid, ClearMode::Match { min_group: 3 });
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].unlocked, vec![(0, 1)]);
+        assert_eq!(grid, vec![vec![R, N], vec![N, N], vec![N, N], vec![G, N]]);
+        let mut grid = vec![vec![R, G], vec![L, G], vec![G, N]];
+        assert!(resolve(&mut grid, ClearMode::Rings).is_empty());
+    }
+
+    #[test]
+    fn test_garbage() {
+        let mut grid = vec![vec![R, N, N], vec![G, N, N]];
+        let mut falling = vec![vec![false; 3]; 2];
+        assert!(!push_garbage(&mut grid, &mut falling, 1, 1));
+        assert_eq!(grid, vec![vec![X, R, N], vec![N, G, N]]);
+        assert!(push_garbage(&mut grid, &mut falling, 2, 0));
+        assert_eq!(grid, vec![vec![N, N, X], vec![X, X, N]]);
+        let mut grid = vec![vec![X, R], vec![R, R], vec![R, G]];
+        let steps = resolve(&mut grid, ClearMode::Match { min_group: 3 });
+        assert_eq!(steps.len(), 1);
+        assert_eq!(grid, vec![vec![N, N], vec![N, N], vec![G, N]]);
+    }
+
+    #[test]
+    fn test_garbage_pushes_falling_cells_with_their_column() {
+        // the green cell is still falling above the red one
+        let mut grid = vec![vec![R, N, G, N], vec![N, N, N, N]];
+        let mut falling = vec![vec![false, false, true, false], vec![false; 4]];
+        assert!(!push_garbage(&mut grid, &mut falling, 1, 1));
+        assert_eq!(grid, vec![vec![X, R, N, G], vec![N, N, N, N]]);
+        assert_eq!(
+            falling,
+            vec![vec![false, false, false, true], vec![false; 4]]
+        );
+        assert!(
+            push_garbage(&mut grid, &mut falling, 1, 1),
+            "expected the falling cell to count as pushed off"
+        );
+        assert_eq!(grid, vec![vec![X, X, R, N], vec![N, N, N, N]]);
+        assert_eq!(falling, vec![vec![false; 4]; 2]);
+    }
+}
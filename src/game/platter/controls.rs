@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::input::Action;
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.add_event::<PlatterInput>();
+}
+
+/// Which player's [`Action`]s spin and drop blocks on a single platter.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct PlatterControls {
+    pub player: usize,
+}
+
+impl PlatterControls {
+    pub fn for_player(player: usize) -> Self {
+        Self { player }
+    }
+    pub fn spin_left(&self) -> Action {
+        Action::SpinLeft(self.player)
+    }
+    pub fn spin_right(&self) -> Action {
+        Action::SpinRight(self.player)
+    }
+    pub fn drop(&self) -> Action {
+        Action::Drop(self.player)
+    }
+}
+
+/// Sent when a player starts spinning a platter or drops a block, for feedback like sounds.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct PlatterInput {
+    pub platter: Entity,
+    pub kind: PlatterInputKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlatterInputKind {
+    SpinLeft,
+    SpinRight,
+    Drop,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
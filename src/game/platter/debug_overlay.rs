@@ -0,0 +1,144 @@
+//! World-space labels showing which segment is which, toggled with F3.
+
+use bevy::ecs::entity::EntityHashSet;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::falling::FallingBlock;
+use crate::game::platter::mesh::PlatterSegmentMesh;
+use crate::game::platter::segment::{CenterPoint, PlatterSegment};
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<SegmentDebugOverlay>();
+    app.add_systems(
+        Update,
+        (
+            toggle_overlay.run_if(input_just_pressed(KeyCode::F3)),
+            despawn_labels,
+            spawn_labels.run_if(overlay_enabled),
+            update_labels.run_if(overlay_enabled),
+        )
+            .chain(),
+    );
+}
+
+/// Drawn above everything else on the platter.
+const LABEL_Z: f32 = 100.0;
+
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct SegmentDebugOverlay {
+    pub enabled: bool,
+    #[default(8.0)]
+    pub font_size: f32,
+    #[default(Color::WHITE)]
+    pub color: Color,
+}
+
+/// Label following the centre of `segment`, kept upright instead of spinning with the platter.
+#[derive(Component, Debug, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct SegmentDebugLabel {
+    segment: Entity,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn overlay_enabled(overlay: Res<SegmentDebugOverlay>) -> bool {
+    overlay.enabled
+}
+
+fn toggle_overlay(mut overlay: ResMut<SegmentDebugOverlay>) {
+    overlay.enabled = !overlay.enabled;
+}
+
+fn despawn_labels(
+    mut commands: Commands,
+    overlay: Res<SegmentDebugOverlay>,
+    labels_q: Query<(Entity, &SegmentDebugLabel)>,
+    segments_q: Query<(), With<PlatterSegment>>,
+) {
+    for (entity, label) in labels_q.iter() {
+        if overlay.enabled && segments_q.contains(label.segment) {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_labels(
+    mut commands: Commands,
+    overlay: Res<SegmentDebugOverlay>,
+    labels_q: Query<&SegmentDebugLabel>,
+    segments_q: Query<Entity, With<PlatterSegment>>,
+) {
+    let labelled = labels_q
+        .iter()
+        .map(|label| label.segment)
+        .collect::<EntityHashSet>();
+    for segment in segments_q.iter() {
+        if labelled.contains(&segment) {
+            continue;
+        }
+        commands.spawn((
+            Name::new("SegmentDebugLabel"),
+            SegmentDebugLabel { segment },
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: overlay.font_size,
+                        color: overlay.color,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn update_labels(
+    overlay: Res<SegmentDebugOverlay>,
+    mut labels_q: Query<(&SegmentDebugLabel, &mut Text, &mut Transform)>,
+    segments_q: Query<(&PlatterSegmentMesh, &CenterPoint, Has<FallingBlock>), With<PlatterSegment>>,
+) {
+    for (label, mut text, mut transform) in labels_q.iter_mut() {
+        let Some((psm, center_point, is_falling)) = segments_q.get(label.segment).ok() else {
+            continue;
+        };
+        let center = center_point.get();
+        let value = format!(
+            "({},{})\n{:.0},{:.0}{}",
+            psm.pie_cut,
+            psm.onion_layer,
+            center.x,
+            center.y,
+            if is_falling { "\nfalling" } else { "" },
+        );
+        let up_to_date = text
+            .sections
+            .first()
+            .is_some_and(|section| section.value == value);
+        if !up_to_date || overlay.is_changed() {
+            *text = Text::from_section(
+                value,
+                TextStyle {
+                    font_size: overlay.font_size,
+                    color: overlay.color,
+                    ..default()
+                },
+            );
+        }
+        let translation = center.extend(LABEL_Z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
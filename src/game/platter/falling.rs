@@ -14,17 +14,23 @@ use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 use internal_shared::register_type_binder::RegisterTypeBinder;
 
 use crate::game::platter::arm::PlatterArm;
+use crate::game::platter::game_over::GameOver;
+use crate::game::platter::index::{PlatterSegmentIndex, PolarPosition};
 use crate::game::platter::mesh::{PlatterMeshes, PlatterMeshOptions, PlatterSegmentMesh};
 use crate::game::platter::platter::Platter;
 use crate::game::platter::segment::{CenterPoint, PlatterSegment};
 use crate::game::platter::spawn::{SpawnArea, SpawnAreaBundle};
-use crate::game::platter::value::{BlockGrid, InnerValue, OriginType, PlatterSegmentValue};
+use crate::game::platter::value::{
+    BlockGrid, CellValue, InnerValue, OriginType, PlatterSegmentValue,
+};
 
 pub(crate) fn plugin(app: &mut App) {
     Types.register_types(app);
     // app.add_systems(Update, render.after(PhysicsStepSet::ReportContacts));
     app.add_event::<SpawnFallingBlock>();
     app.add_event::<SpawnFallingBlockFailed>();
+    app.add_event::<FallingBlockSpawned>();
+    app.add_event::<BlockLanded>();
     app.configure_sets(Update, FallingSystemSet);
     app.add_systems(Update, spawn_falling_block.in_set(FallingSystemSet));
     app.add_systems(
@@ -39,7 +45,7 @@ pub struct FallingSystemSet;
 #[derive(Event, Debug, Copy, Clone)]
 pub struct SpawnFallingBlock {
     pub platter: Entity,
-    pub value: InnerValue,
+    pub value: CellValue,
 }
 
 #[derive(Event, Debug, Copy, Clone)]
@@ -47,6 +53,18 @@ pub struct SpawnFallingBlockFailed {
     pub platter: Entity,
 }
 
+/// Sent when a falling block was placed without overlapping settled segments.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct FallingBlockSpawned {
+    pub platter: Entity,
+}
+
+/// Sent once all cells of a falling block have settled on a platter.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct BlockLanded {
+    pub platter: Entity,
+}
+
 #[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
 #[reflect(Component)]
 pub struct FallingBlock;
@@ -57,6 +75,7 @@ pub struct Types;
 #[derive(QueryData)]
 struct FallingQueryData<'w> {
     entity: Entity,
+    parent: &'w Parent,
     platter_segment_value: &'w PlatterSegmentValue,
     platter_segment_mesh: &'w PlatterSegmentMesh,
 }
@@ -65,73 +84,88 @@ struct FallingQueryData<'w> {
 struct SegmentQueryData<'w> {
     entity: Entity,
     platter_segment_value: &'w PlatterSegmentValue,
-    platter_segment_mesh: &'w PlatterSegmentMesh,
     has_falling_block: Has<FallingBlock>,
 }
 
 fn do_fall(
     mut commands: Commands,
+    index: Res<PlatterSegmentIndex>,
     falling_q: Query<FallingQueryData, (With<PlatterSegment>, With<FallingBlock>)>,
     segments_q: Query<SegmentQueryData, With<PlatterSegment>>,
+    mut block_landed: EventWriter<BlockLanded>,
 ) {
-    let current = falling_q.iter().collect::<Vec<_>>();
-    let next_unchecked = current
-        .iter()
-        .map(|cur| {
-            let mut pie_cut = cur.platter_segment_mesh.pie_cut;
-            let mut onion_layer = cur.platter_segment_mesh.onion_layer;
-            onion_layer -= 1;
-            let next = segments_q.iter().find(|next| {
-                next.platter_segment_value.0.is_none()
-                    && next.platter_segment_mesh.pie_cut == pie_cut
-                    && next.platter_segment_mesh.onion_layer == onion_layer
-            });
-            (
-                (pie_cut, onion_layer),
-                cur.platter_segment_value,
-                next.map(|seg| seg.entity),
-            )
-        })
-        .collect::<Vec<_>>();
-    let mut next = vec![];
-    for (pos, valiue, entity_opt) in next_unchecked.into_iter() {
-        let Some(next_entity) = entity_opt else {
-            // TODO: cant fall anymore apply
-            return;
+    let current_by_platter = falling_q.iter().into_group_map_by(|cur| cur.parent.get());
+    for (platter, current) in current_by_platter {
+        let Some(polar_index) = index.get(platter) else {
+            continue;
         };
-        next.push((pos, valiue, next_entity));
-    }
-    for item in current.into_iter() {
-        commands
-            .entity(item.entity)
-            .remove::<FallingBlock>()
-            .insert(PlatterSegmentValue::default());
-    }
-    for &((pie_cut, onion_layer), value, entity) in next.iter() {
-        commands.entity(entity).insert((FallingBlock, *value));
+        let next = current
+            .iter()
+            .map(|cur| {
+                let pos = (
+                    cur.platter_segment_mesh.pie_cut,
+                    cur.platter_segment_mesh.onion_layer,
+                );
+                let below = polar_index.inward(pos)?;
+                segments_q
+                    .get(below)
+                    .ok()
+                    // cells of the same falling block are about to vacate
+                    .filter(|next| next.platter_segment_value.0.is_none() || next.has_falling_block)
+                    .map(|seg| (cur.platter_segment_value, seg.entity))
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(next) = next else {
+            // cant fall anymore, settle in place
+            for item in current.into_iter() {
+                commands.entity(item.entity).remove::<FallingBlock>();
+            }
+            block_landed.send(BlockLanded { platter });
+            continue;
+        };
+        for item in current.into_iter() {
+            commands
+                .entity(item.entity)
+                .remove::<FallingBlock>()
+                .insert(PlatterSegmentValue::default());
+        }
+        for (value, entity) in next.into_iter() {
+            commands.entity(entity).insert((FallingBlock, *value));
+        }
     }
 }
 
 fn spawn_falling_block(
     mut commands: Commands,
-    platter_q: Query<(Entity, &PlatterMeshOptions, &GlobalTransform), With<Platter>>,
-    spawn_area_q: Query<&CollidingEntities, With<SpawnArea>>,
-    mut segments_q: Query<
-        (&PlatterSegmentMesh, &CenterPoint, Mut<PlatterSegmentValue>),
+    platter_q: Query<(Entity, &PlatterMeshOptions, &GlobalTransform, Has<GameOver>), With<Platter>>,
+    spawn_area_q: Query<(&SpawnArea, &CollidingEntities)>,
+    index: Res<PlatterSegmentIndex>,
+    segments_q: Query<
+        (&PlatterSegmentMesh, &CenterPoint, &PlatterSegmentValue),
         With<PlatterSegment>,
     >,
     mut spawn_falling_block: EventReader<SpawnFallingBlock>,
     mut spawn_falling_block_failed: EventWriter<SpawnFallingBlockFailed>,
+    mut falling_block_spawned: EventWriter<FallingBlockSpawned>,
 ) {
     'event: for &event in spawn_falling_block.read() {
         log::debug!("SpawnFallingBlock: {event:?}");
-        let Some((platter_entity, pmo, global_transform)) = platter_q.get(event.platter).ok()
+        let Some((platter_entity, pmo, global_transform, is_game_over)) =
+            platter_q.get(event.platter).ok()
         else {
             panic!("failed to find platter");
         };
-        // TODO: make ti a child
-        let Some(colliding) = spawn_area_q.get_single().ok() else {
-            panic!("failed to find SpawnArea")
+        if is_game_over {
+            continue;
+        }
+        let Some((_, colliding)) = spawn_area_q
+            .iter()
+            .find(|(spawn_area, _)| spawn_area.platter == platter_entity)
+        else {
+            panic!("failed to find SpawnArea for {platter_entity}")
+        };
+        let Some(polar_index) = index.get(platter_entity) else {
+            panic!("missing segment index for {platter_entity}")
         };
         let top_row = pmo.get().onion_layers - 1;
         log::debug!("top_row: {top_row}");
@@ -142,47 +176,36 @@ fn spawn_falling_block(
             layer: usize,
             slice: usize,
             center: Vec2,
-            value: Option<InnerValue>,
+            value: Option<CellValue>,
         }
 
-        let mut targets = HashMap::<usize, EntityHashMap<Segment>>::default();
-        for &collided in colliding.0.iter() {
-            let Some((psm, center, psv)) = segments_q.get(collided).ok() else {
-                continue;
-            };
-            match event.value {
-                InnerValue::RedZ
-                | InnerValue::GreenS
-                | InnerValue::PurpleT
-                | InnerValue::BlueJ
-                | InnerValue::OrangeL
-                | InnerValue::YellowO => {
-                    if psm.onion_layer < top_row - 3 {
-                        continue;
-                    }
-                }
-                InnerValue::CyanI => {
-                    if psm.onion_layer < top_row - 4 {
-                        continue;
-                    }
-                }
-            }
-            targets.entry(psm.onion_layer).or_default().insert(
-                collided,
-                Segment {
-                    entity: collided,
-                    layer: psm.onion_layer,
-                    slice: psm.pie_cut,
-                    center: center.get(),
-                    value: psv.0,
-                },
-            );
-        }
+        let segment_at = |pos: PolarPosition| -> Option<Segment> {
+            let entity = polar_index.get(pos)?;
+            let (_, center, psv) = segments_q.get(entity).ok()?;
+            Some(Segment {
+                entity,
+                layer: pos.1,
+                slice: pos.0,
+                center: center.get(),
+                value: psv.0,
+            })
+        };
+
+        // only the top row needs the spawn area, the rows below are looked up by position
+        let top_targets = colliding
+            .0
+            .iter()
+            .filter_map(|&collided| {
+                let (psm, _, _) = segments_q.get(collided).ok()?;
+                let pos = (psm.pie_cut, psm.onion_layer);
+                // the index only holds segments of this platter
+                (pos.1 == top_row && polar_index.get(pos) == Some(collided)).then_some(pos)
+            })
+            .filter_map(segment_at)
+            .collect::<Vec<_>>();
         let platter_x = global_transform.translation().x;
-        let closest_targets = targets
-            .get(&top_row)
-            .expect("missing top row")
-            .values()
+        let closest_targets = top_targets
+            .iter()
             .sorted_by(|a, b| {
                 platter_x
                     .distance_squared(a.center.x)
@@ -237,25 +260,18 @@ fn spawn_falling_block(
 
         fn populate<const W: usize, const H: usize>(
             grid: &mut [[Option<Segment>; W]; H],
-            targets: &HashMap<usize, EntityHashMap<Segment>>,
+            segment_at: impl Fn(PolarPosition) -> Option<Segment>,
         ) {
-            let Some(&top_row) = targets.keys().max() else {
-                panic!("empty targets");
-            };
             let fr = grid[0];
             for (row_ix, columns) in (0..grid.len() - 1).map(|c| (c + 1, fr)) {
                 for (col_ix, column) in columns.iter().enumerate() {
                     let Some(column) = column else {
                         unreachable!();
                     };
-                    let ix = top_row - row_ix;
-                    let value = targets
-                        .get(&ix)
-                        .expect("missing row")
-                        .values()
-                        .find(|&seg| top_row - seg.layer == row_ix && seg.slice == column.slice)
-                        .copied();
-                    let Some(value) = value else {
+                    let Some(ix) = column.layer.checked_sub(row_ix) else {
+                        panic!("not enough onion layers for row {row_ix}")
+                    };
+                    let Some(value) = segment_at((column.slice, ix)) else {
                         panic!("empty value for layer {ix}")
                     };
                     log::debug!("setting [{row_ix}][{col_ix}] = {value:?}");
@@ -272,19 +288,21 @@ fn spawn_falling_block(
 
         match grid {
             BlockGrid::ThreeByThree(ref mut grid) => {
-                populate(grid, &targets);
+                populate(grid, &segment_at);
             }
             BlockGrid::ThreeByFour(ref mut grid) => {
-                populate(grid, &targets);
+                populate(grid, &segment_at);
             }
             BlockGrid::FourByFour(ref mut grid) => {
-                populate(grid, &targets);
+                populate(grid, &segment_at);
             }
         }
 
         let shape_grid = event.value.shape_coordinates();
         debug_assert!(grid.is_same_size(&shape_grid));
 
+        let mut failed = false;
+
         for (row_ix, row) in grid.iter().enumerate() {
             for (col_ix, col) in row.iter().enumerate() {
                 let Some(seg) = col else {
@@ -292,6 +310,7 @@ fn spawn_falling_block(
                 };
                 if shape_grid.get(row_ix, col_ix) {
                     if seg.value.is_some() {
+                        failed = true;
                         spawn_falling_block_failed.send(SpawnFallingBlockFailed {
                             platter: platter_entity,
                         });
@@ -313,6 +332,11 @@ fn spawn_falling_block(
                 }
             }
         }
+        if !failed {
+            falling_block_spawned.send(FallingBlockSpawned {
+                platter: platter_entity,
+            });
+        }
     }
 }
 
@@ -326,7 +350,7 @@ fn render(
             let Some(mut psv) = segments_q.get_mut(colliding_entity).ok() else {
                 continue;
             };
-            psv.0.replace(InnerValue::RedZ);
+            psv.0.replace(CellValue::Block(InnerValue::RedZ));
         }
     }
 }
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::clear::{ClearSystemSet, GarbageOverflow};
+use crate::game::platter::falling::SpawnFallingBlockFailed;
+use crate::game::platter::platter::Platter;
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.add_event::<PlatterGameOver>();
+    app.add_systems(Update, mark_game_over.after(ClearSystemSet));
+}
+
+/// Marks a platter that can no longer spawn blocks.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct GameOver;
+
+/// Sent once when a platter is marked with [`GameOver`].
+#[derive(Event, Debug, Copy, Clone)]
+pub struct PlatterGameOver {
+    pub platter: Entity,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn mark_game_over(
+    mut commands: Commands,
+    mut spawn_falling_block_failed: EventReader<SpawnFallingBlockFailed>,
+    mut garbage_overflow: EventReader<GarbageOverflow>,
+    platter_q: Query<Has<GameOver>, With<Platter>>,
+    mut platter_game_over: EventWriter<PlatterGameOver>,
+) {
+    let mut platters = spawn_falling_block_failed
+        .read()
+        .map(|event| event.platter)
+        .chain(garbage_overflow.read().map(|event| event.platter))
+        .collect::<Vec<_>>();
+    platters.sort_unstable();
+    platters.dedup();
+    for platter in platters {
+        let Some(is_game_over) = platter_q.get(platter).ok() else {
+            continue;
+        };
+        if is_game_over {
+            continue;
+        }
+        log::debug!("game over for {platter}");
+        commands.entity(platter).insert(GameOver);
+        platter_game_over.send(PlatterGameOver { platter });
+    }
+}
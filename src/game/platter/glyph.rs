@@ -0,0 +1,231 @@
+//! Colour-blind friendly glyphs drawn over filled segments, one distinct shape per [`InnerValue`].
+
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+
+use bevy::prelude::*;
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::mesh::PlatterSegmentMesh;
+use crate::game::platter::segment::PlatterSegment;
+use crate::game::platter::value::{InnerValue, PlatterSegmentValue};
+use crate::game::util::mesh::{calculate_centroid, ColoredMeshBuilder};
+use crate::ui::theme::{CurrentTheme, ThemeChanged};
+use crate::util::prototype_mesh_manager::{PrototypeMesh, PrototypeMeshKey};
+use crate::util::PrototypeManagerSystemParam;
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<GlyphOverlay>();
+    app.add_systems(
+        Update,
+        (
+            update_glyphs,
+            recolor_glyphs.run_if(on_event::<ThemeChanged>()),
+        ),
+    );
+}
+
+/// Settings of the glyph overlay.
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct GlyphOverlay {
+    pub enabled: bool,
+    /// Glyph size relative to the smaller side of its segment.
+    #[default(0.6)]
+    pub scale: f32,
+}
+
+/// Glyph drawn as a child of a segment.
+#[derive(Component, Debug, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct SegmentGlyph(pub InnerValue);
+
+/// Unit sized glyph of a value, scaled to the segment by the transform.
+#[derive(Debug, Copy, Clone)]
+pub struct BlockGlyphMesh(pub InnerValue);
+
+impl PrototypeMesh for BlockGlyphMesh {
+    fn get_key(&self) -> PrototypeMeshKey {
+        PrototypeMeshKey::new("BlockGlyphMesh").with_u32(self.0 as u32)
+    }
+
+    fn get_mesh(&self) -> Mesh {
+        let mut builder = ColoredMeshBuilder::default();
+        for (vertices, triangles) in glyph_shapes(self.0) {
+            // the material provides the colour
+            builder.push(&vertices, &triangles, Color::WHITE);
+        }
+        builder.build()
+    }
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+type Shape = (Vec<Vec2>, Vec<[usize; 3]>);
+
+const STROKE: f32 = 0.18;
+
+/// Shapes fitting in a unit square centred on the origin, `+Y` points away from the platter centre.
+fn glyph_shapes(value: InnerValue) -> Vec<Shape> {
+    match value {
+        InnerValue::RedZ => vec![
+            bar(Vec2::new(-0.35, -0.35), Vec2::new(0.35, 0.35)),
+            bar(Vec2::new(-0.35, 0.35), Vec2::new(0.35, -0.35)),
+        ],
+        InnerValue::GreenS => vec![
+            bar(Vec2::new(-0.4, 0.0), Vec2::new(0.4, 0.0)),
+            bar(Vec2::new(0.0, -0.4), Vec2::new(0.0, 0.4)),
+        ],
+        InnerValue::YellowO => outline(&regular_polygon(16, 0.35, 0.0)),
+        InnerValue::PurpleT => vec![polygon(regular_polygon(3, 0.45, FRAC_PI_2))],
+        InnerValue::BlueJ => outline(&regular_polygon(4, 0.45, FRAC_PI_4)),
+        InnerValue::OrangeL => vec![polygon(regular_polygon(4, 0.45, 0.0))],
+        InnerValue::CyanI => [-0.3, 0.0, 0.3]
+            .into_iter()
+            .map(|y| bar(Vec2::new(-0.4, y), Vec2::new(0.4, y)))
+            .collect(),
+    }
+}
+
+/// Square capped stroke from `from` to `to`, counter-clockwise.
+fn bar(from: Vec2, to: Vec2) -> Shape {
+    let direction = (to - from).normalize() * STROKE / 2.0;
+    let offset = direction.perp();
+    let (from, to) = (from - direction, to + direction);
+    (
+        vec![from - offset, to - offset, to + offset, from + offset],
+        vec![[0, 1, 2], [0, 2, 3]],
+    )
+}
+
+/// Fan triangulation of a convex polygon.
+fn polygon(vertices: Vec<Vec2>) -> Shape {
+    let triangles = (1..vertices.len() - 1).map(|ix| [0, ix, ix + 1]).collect();
+    (vertices, triangles)
+}
+
+fn outline(vertices: &[Vec2]) -> Vec<Shape> {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&from, &to)| bar(from, to))
+        .collect()
+}
+
+fn regular_polygon(sides: usize, radius: f32, rotation: f32) -> Vec<Vec2> {
+    (0..sides)
+        .map(|ix| Vec2::from_angle(rotation + TAU * ix as f32 / sides as f32) * radius)
+        .collect()
+}
+
+/// Local transform placing a unit glyph in the middle of a segment, upright towards the rim.
+fn glyph_transform(psm: &PlatterSegmentMesh, scale: f32) -> Transform {
+    let options = psm.options;
+    let depth = (options.outer_radius - options.inner_radius) / options.onion_layers as f32;
+    let radius = options.inner_radius + depth * (psm.onion_layer as f32 + 0.5);
+    let angle = calculate_centroid(&psm.vertices).to_angle();
+    let arc = TAU * radius / options.pie_cuts as f32;
+    Transform {
+        translation: (Vec2::from_angle(angle) * radius).extend(1.0),
+        rotation: Quat::from_rotation_z(angle - FRAC_PI_2),
+        scale: Vec3::splat(depth.min(arc) * scale),
+    }
+}
+
+fn update_glyphs(
+    mut commands: Commands,
+    mut prototype_manager_system_param: PrototypeManagerSystemParam,
+    theme: CurrentTheme,
+    glyph_overlay: Res<GlyphOverlay>,
+    segments_q: Query<
+        (
+            Entity,
+            Ref<PlatterSegmentValue>,
+            &PlatterSegmentMesh,
+            Option<&Children>,
+        ),
+        With<PlatterSegment>,
+    >,
+    glyphs_q: Query<&SegmentGlyph>,
+) {
+    let overlay_changed = glyph_overlay.is_changed();
+    for (entity, value, psm, children) in segments_q.iter() {
+        if !overlay_changed && !value.is_changed() {
+            continue;
+        }
+        let wanted = value
+            .0
+            .and_then(|value| value.inner())
+            .filter(|_| glyph_overlay.enabled);
+        let mut has_wanted = false;
+        for &child in children.into_iter().flatten() {
+            let Some(glyph) = glyphs_q.get(child).ok() else {
+                continue;
+            };
+            if !overlay_changed && Some(glyph.0) == wanted {
+                has_wanted = true;
+                continue;
+            }
+            commands.entity(child).despawn_recursive();
+        }
+        let Some(inner) = wanted else {
+            continue;
+        };
+        if has_wanted {
+            continue;
+        }
+        let mut color_mesh_2d_bundle = prototype_manager_system_param
+            .get_or_create_color_mesh_2d(&BlockGlyphMesh(inner), theme.get().glyph);
+        color_mesh_2d_bundle.transform = glyph_transform(psm, glyph_overlay.scale);
+        // batched platters hide their segments, the glyph has to stay visible regardless
+        color_mesh_2d_bundle.visibility = Visibility::Visible;
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                Name::new("SegmentGlyph"),
+                SegmentGlyph(inner),
+                color_mesh_2d_bundle,
+            ));
+        });
+    }
+}
+
+fn recolor_glyphs(
+    mut commands: Commands,
+    mut prototype_manager_system_param: PrototypeManagerSystemParam,
+    theme: CurrentTheme,
+    glyphs_q: Query<Entity, With<SegmentGlyph>>,
+) {
+    let material = prototype_manager_system_param.get_or_create_material(theme.get().glyph);
+    for entity in glyphs_q.iter() {
+        commands.entity(entity).insert(material.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyph_shapes_are_counter_clockwise() {
+        for value in [
+            InnerValue::RedZ,
+            InnerValue::GreenS,
+            InnerValue::YellowO,
+            InnerValue::PurpleT,
+            InnerValue::BlueJ,
+            InnerValue::OrangeL,
+            InnerValue::CyanI,
+        ] {
+            for (vertices, triangles) in glyph_shapes(value) {
+                for [a, b, c] in triangles {
+                    let (a, b, c) = (vertices[a], vertices[b], vertices[c]);
+                    assert!((b - a).perp_dot(c - a) > 0.0, "{value:?}");
+                }
+            }
+        }
+    }
+}
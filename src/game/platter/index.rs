@@ -0,0 +1,197 @@
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+
+use crate::game::platter::falling::FallingSystemSet;
+use crate::game::platter::mesh::{PlatterMeshOptions, PlatterSegmentMesh};
+use crate::game::platter::platter::Platter;
+use crate::game::platter::segment::PlatterSegment;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<PlatterSegmentIndex>();
+    app.add_systems(
+        Update,
+        (remove_despawned_platters, index_segments)
+            .chain()
+            .before(FallingSystemSet),
+    );
+}
+
+/// `(pie_cut, onion_layer)` of a segment, pie cuts grow counter-clockwise and onion layers outward.
+pub type PolarPosition = (usize, usize);
+
+/// Size of a platter's polar grid, answers neighbour queries without needing any segments.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PolarDimensions {
+    pub pie_cuts: usize,
+    pub onion_layers: usize,
+}
+
+impl PolarDimensions {
+    pub fn new(pie_cuts: usize, onion_layers: usize) -> Self {
+        Self {
+            pie_cuts,
+            onion_layers,
+        }
+    }
+    pub fn contains(&self, (pie_cut, onion_layer): PolarPosition) -> bool {
+        pie_cut < self.pie_cuts && onion_layer < self.onion_layers
+    }
+    /// The position one onion layer closer to the centre.
+    pub fn inward(&self, (pie_cut, onion_layer): PolarPosition) -> Option<PolarPosition> {
+        onion_layer.checked_sub(1).map(|layer| (pie_cut, layer))
+    }
+    /// The position one onion layer further from the centre.
+    pub fn outward(&self, (pie_cut, onion_layer): PolarPosition) -> Option<PolarPosition> {
+        (onion_layer + 1 < self.onion_layers).then_some((pie_cut, onion_layer + 1))
+    }
+    /// The position in the next pie cut clockwise, wrapping around.
+    pub fn clockwise(&self, (pie_cut, onion_layer): PolarPosition) -> Option<PolarPosition> {
+        (self.pie_cuts > 1).then(|| ((pie_cut + self.pie_cuts - 1) % self.pie_cuts, onion_layer))
+    }
+    /// The position in the next pie cut counter-clockwise, wrapping around.
+    pub fn counter_clockwise(
+        &self,
+        (pie_cut, onion_layer): PolarPosition,
+    ) -> Option<PolarPosition> {
+        (self.pie_cuts > 1).then(|| ((pie_cut + 1) % self.pie_cuts, onion_layer))
+    }
+    /// All direct neighbours, with only two pie cuts clockwise and counter-clockwise are the same.
+    pub fn neighbours(&self, pos: PolarPosition) -> impl Iterator<Item = PolarPosition> {
+        let counter_clockwise = self.counter_clockwise(pos);
+        let clockwise = self
+            .clockwise(pos)
+            .filter(|&clockwise| Some(clockwise) != counter_clockwise);
+        [
+            self.inward(pos),
+            self.outward(pos),
+            counter_clockwise,
+            clockwise,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Maps every [`PolarPosition`] of a single platter to its segment entity.
+#[derive(Debug, Default, Clone)]
+pub struct PolarIndex {
+    dimensions: PolarDimensions,
+    segments: Vec<Option<Entity>>,
+}
+
+impl PolarIndex {
+    pub fn new(dimensions: PolarDimensions) -> Self {
+        Self {
+            dimensions,
+            segments: vec![None; dimensions.pie_cuts * dimensions.onion_layers],
+        }
+    }
+    pub fn dimensions(&self) -> PolarDimensions {
+        self.dimensions
+    }
+    fn slot(&self, pos: PolarPosition) -> Option<usize> {
+        self.dimensions
+            .contains(pos)
+            .then_some(pos.0 * self.dimensions.onion_layers + pos.1)
+    }
+    pub fn insert(&mut self, pos: PolarPosition, entity: Entity) {
+        let Some(slot) = self.slot(pos) else {
+            panic!("{pos:?} out of bounds for {:?}", self.dimensions);
+        };
+        self.segments[slot] = Some(entity);
+    }
+    pub fn get(&self, pos: PolarPosition) -> Option<Entity> {
+        self.slot(pos).and_then(|slot| self.segments[slot])
+    }
+    pub fn inward(&self, pos: PolarPosition) -> Option<Entity> {
+        self.get(self.dimensions.inward(pos)?)
+    }
+    pub fn outward(&self, pos: PolarPosition) -> Option<Entity> {
+        self.get(self.dimensions.outward(pos)?)
+    }
+    pub fn clockwise(&self, pos: PolarPosition) -> Option<Entity> {
+        self.get(self.dimensions.clockwise(pos)?)
+    }
+    pub fn counter_clockwise(&self, pos: PolarPosition) -> Option<Entity> {
+        self.get(self.dimensions.counter_clockwise(pos)?)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (PolarPosition, Entity)> + '_ {
+        let onion_layers = self.dimensions.onion_layers;
+        self.segments
+            .iter()
+            .enumerate()
+            .filter_map(move |(slot, entity)| {
+                entity.map(|entity| ((slot / onion_layers, slot % onion_layers), entity))
+            })
+    }
+}
+
+/// [`PolarIndex`] of every platter keyed by the platter entity.
+#[derive(Resource, Debug, Default)]
+pub struct PlatterSegmentIndex(EntityHashMap<PolarIndex>);
+
+impl PlatterSegmentIndex {
+    pub fn get(&self, platter: Entity) -> Option<&PolarIndex> {
+        self.0.get(&platter)
+    }
+}
+
+fn index_segments(
+    mut index: ResMut<PlatterSegmentIndex>,
+    platter_q: Query<&PlatterMeshOptions, With<Platter>>,
+    segments_q: Query<(Entity, &Parent, &PlatterSegmentMesh), Added<PlatterSegment>>,
+) {
+    for (entity, parent, psm) in segments_q.iter() {
+        let platter = parent.get();
+        let Some(pmo) = platter_q.get(platter).ok() else {
+            continue;
+        };
+        let options = pmo.get();
+        index
+            .0
+            .entry(platter)
+            .or_insert_with(|| {
+                PolarIndex::new(PolarDimensions::new(options.pie_cuts, options.onion_layers))
+            })
+            .insert((psm.pie_cut, psm.onion_layer), entity);
+    }
+}
+
+fn remove_despawned_platters(
+    mut index: ResMut<PlatterSegmentIndex>,
+    mut removed_platters: RemovedComponents<Platter>,
+) {
+    for platter in removed_platters.read() {
+        index.0.remove(&platter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours_wrap() {
+        let dimensions = PolarDimensions::new(4, 3);
+        assert_eq!(dimensions.inward((0, 0)), None);
+        assert_eq!(dimensions.inward((1, 2)), Some((1, 1)));
+        assert_eq!(dimensions.outward((1, 2)), None);
+        assert_eq!(dimensions.outward((1, 1)), Some((1, 2)));
+        assert_eq!(dimensions.clockwise((0, 1)), Some((3, 1)));
+        assert_eq!(dimensions.counter_clockwise((3, 1)), Some((0, 1)));
+        assert_eq!(PolarDimensions::new(1, 3).clockwise((0, 1)), None);
+    }
+
+    #[test]
+    fn test_get_and_iter() {
+        let mut index = PolarIndex::new(PolarDimensions::new(2, 2));
+        let entity = Entity::from_raw(7);
+        index.insert((1, 0), entity);
+        assert_eq!(index.get((1, 0)), Some(entity));
+        assert_eq!(index.get((0, 1)), None);
+        assert_eq!(index.get((2, 0)), None);
+        assert_eq!(index.outward((1, 1)), None);
+        assert_eq!(index.inward((1, 1)), Some(entity));
+        assert_eq!(index.iter().collect::<Vec<_>>(), vec![((1, 0), entity)]);
+    }
+}
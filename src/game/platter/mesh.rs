@@ -1,3 +1,5 @@
+use std::f32::consts::TAU;
+
 use bevy::color::Color;
 use bevy::prelude::*;
 use bevy::render::mesh::{Indices, PrimitiveTopology};
@@ -6,10 +8,12 @@ use smart_default::SmartDefault;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
+use crate::game::platter::batched::PlatterRenderMode;
 use crate::game::util::mesh::{
-    generate_donut_vertices, generate_subdivided_donut_split_vertices, line_strip_2d_to_mesh,
+    annular_sector_to_mesh, annular_sector_triangulate, donut_vertex_uvs, generate_donut_vertices,
+    generate_subdivided_donut_split_vertices, radial_quad, ColoredMeshBuilder,
 };
-use crate::util::prototype_mesh_manager::{PrototypeMesh, PrototypeMeshId};
+use crate::util::prototype_mesh_manager::{PrototypeMesh, PrototypeMeshKey};
 
 pub(crate) fn plugin(app: &mut App) {
     Types.register_types(app);
@@ -43,6 +47,31 @@ pub struct PlatterMeshOptionsObj {
     #[default(Color::BLACK)]
     pub main_color: Color,
     pub initial_segment_color: Color,
+    pub uv_mode: PolarUvMode,
+    pub render_mode: PlatterRenderMode,
+    /// Borders drawn along the onion layer rings.
+    pub ring_border: PlatterBorderStyle,
+    /// Borders drawn along the pie cuts.
+    pub pie_cut_border: PlatterBorderStyle,
+}
+
+#[derive(Debug, SmartDefault, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub struct PlatterBorderStyle {
+    /// Width in world units, `0.0` disables the border.
+    #[default(1.0)]
+    pub width: f32,
+    #[default(Color::BLACK)]
+    pub color: Color,
+}
+
+/// How textures are stretched over the platter segments.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect, AutoRegisterType)]
+pub enum PolarUvMode {
+    /// Every segment maps the full texture, U along the angle and V along the radius.
+    #[default]
+    PerSegment,
+    /// The texture wraps once around the whole platter.
+    Platter,
 }
 
 #[derive(Component, Debug, Default, Clone, Reflect, AutoRegisterType)]
@@ -61,9 +90,23 @@ pub(super) struct PlatterSegmentMesh {
     pub(super) vertices: Vec<Vec2>,
 }
 
+/// Ring and pie-cut borders of a platter drawn as one vertex coloured mesh.
+#[derive(Component, Debug, Default, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub(super) struct PlatterBorderMesh {
+    pub(super) options: PlatterMeshOptionsObj,
+}
+
+impl PlatterBorderMesh {
+    pub(super) fn is_empty(&self) -> bool {
+        self.options.ring_border.width <= 0.0 && self.options.pie_cut_border.width <= 0.0
+    }
+}
+
 pub(super) struct PlatterMeshes {
     pub(super) main: PlatterMainMesh,
     pub(super) segments: Vec<PlatterSegmentMesh>,
+    pub(super) borders: PlatterBorderMesh,
 }
 
 impl From<PlatterMeshOptionsObj> for PlatterMeshes {
@@ -104,27 +147,129 @@ impl From<PlatterMeshOptionsObj> for PlatterMeshes {
                 ),
             },
             segments,
+            borders: PlatterBorderMesh { options },
         }
     }
 }
 
 impl PrototypeMesh for PlatterMainMesh {
-    fn get_id(&self) -> PrototypeMeshId {
-        format!("{self:?}").into()
+    fn get_key(&self) -> PrototypeMeshKey {
+        PrototypeMeshKey::new("PlatterMainMesh")
+            .with_usize(self.options.inner_resolution)
+            .with_usize(self.options.outer_resolution)
+            .with_vertices(&self.vertices)
+    }
+
+    fn get_mesh(&self) -> Mesh {
+        let uvs = donut_vertex_uvs(
+            self.options.inner_resolution,
+            self.options.outer_resolution,
+            true,
+        );
+        annular_sector_to_mesh(
+            self.vertices.clone(),
+            uvs,
+            self.options.inner_resolution,
+            self.options.outer_resolution,
+        )
+    }
+}
+
+impl PrototypeMesh for PlatterBorderMesh {
+    fn get_key(&self) -> PrototypeMeshKey {
+        let options = self.options;
+        PrototypeMeshKey::new("PlatterBorderMesh")
+            .with_f32(options.inner_radius)
+            .with_f32(options.outer_radius)
+            .with_usize(options.outer_resolution)
+            .with_usize(options.pie_cuts)
+            .with_usize(options.onion_layers)
+            .with_f32(options.ring_border.width)
+            .with_color(options.ring_border.color)
+            .with_f32(options.pie_cut_border.width)
+            .with_color(options.pie_cut_border.color)
     }
 
     fn get_mesh(&self) -> Mesh {
-        line_strip_2d_to_mesh(self.vertices.clone())
+        let options = self.options;
+        let mut builder = ColoredMeshBuilder::default();
+        let ring = options.ring_border;
+        if ring.width > 0.0 {
+            let ring_step =
+                (options.outer_radius - options.inner_radius) / options.onion_layers as f32;
+            for layer in 0..=options.onion_layers {
+                let radius = options.inner_radius + layer as f32 * ring_step;
+                let vertices = generate_donut_vertices(
+                    (radius - ring.width / 2.0).max(0.0),
+                    radius + ring.width / 2.0,
+                    options.outer_resolution,
+                    options.outer_resolution,
+                    true,
+                );
+                let indices =
+                    annular_sector_triangulate(options.outer_resolution, options.outer_resolution);
+                builder.push(&vertices, &indices, ring.color);
+            }
+        }
+        let pie_cut = options.pie_cut_border;
+        // a single pie cut has no cut to draw
+        if pie_cut.width > 0.0 && options.pie_cuts > 1 {
+            let angle_step = TAU / options.pie_cuts as f32;
+            for cut in 0..options.pie_cuts {
+                let vertices = radial_quad(
+                    options.inner_radius,
+                    options.outer_radius,
+                    cut as f32 * angle_step,
+                    pie_cut.width,
+                );
+                builder.push(&vertices, &[[0, 1, 2], [0, 2, 3]], pie_cut.color);
+            }
+        }
+        builder.build()
+    }
+}
+
+impl PlatterSegmentMesh {
+    pub(super) fn uvs(&self) -> Vec<Vec2> {
+        let uvs = donut_vertex_uvs(
+            self.options.inner_resolution,
+            self.options.outer_resolution,
+            true,
+        );
+        match self.options.uv_mode {
+            PolarUvMode::PerSegment => uvs,
+            PolarUvMode::Platter => {
+                let offset = Vec2::new(self.pie_cut as f32, self.onion_layer as f32);
+                let cells = Vec2::new(
+                    self.options.pie_cuts as f32,
+                    self.options.onion_layers as f32,
+                );
+                uvs.into_iter().map(|uv| (uv + offset) / cells).collect()
+            }
+        }
     }
 }
 
 impl PrototypeMesh for PlatterSegmentMesh {
-    fn get_id(&self) -> PrototypeMeshId {
-        format!("{self:?}").into()
+    fn get_key(&self) -> PrototypeMeshKey {
+        PrototypeMeshKey::new("PlatterSegmentMesh")
+            .with_usize(self.options.inner_resolution)
+            .with_usize(self.options.outer_resolution)
+            .with_u32(self.options.uv_mode as u32)
+            .with_usize(self.options.pie_cuts)
+            .with_usize(self.options.onion_layers)
+            .with_usize(self.pie_cut)
+            .with_usize(self.onion_layer)
+            .with_vertices(&self.vertices)
     }
 
     fn get_mesh(&self) -> Mesh {
-        line_strip_2d_to_mesh(self.vertices.clone())
+        annular_sector_to_mesh(
+            self.vertices.clone(),
+            self.uvs(),
+            self.options.inner_resolution,
+            self.options.outer_resolution,
+        )
     }
 }
 
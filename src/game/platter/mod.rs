@@ -3,11 +3,20 @@ use bevy::prelude::*;
 use internal_proc_macros::RegisterTypeBinder;
 
 pub mod arm;
+pub mod batched;
+pub mod clear;
+pub mod controls;
+pub mod debug_overlay;
 pub mod falling;
+pub mod game_over;
+pub mod glyph;
+pub mod index;
 pub mod mesh;
 pub mod platter;
+pub mod score;
 pub mod segment;
 pub mod spawn;
+pub mod spin;
 pub mod value;
 
 pub(crate) fn plugin(app: &mut App) {
@@ -18,6 +27,14 @@ pub(crate) fn plugin(app: &mut App) {
     app.add_plugins(value::plugin);
     app.add_plugins(spawn::plugin);
     app.add_plugins(falling::plugin);
+    app.add_plugins(clear::plugin);
+    app.add_plugins(score::plugin);
+    app.add_plugins(game_over::plugin);
+    app.add_plugins(controls::plugin);
+    app.add_plugins(spin::plugin);
+    app.add_plugins(index::plugin);
+    app.add_plugins(batched::plugin);
+    app.add_plugins(glyph::plugin);
 }
 
 #[derive(RegisterTypeBinder)]
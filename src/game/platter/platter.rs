@@ -10,16 +10,25 @@ use smart_default::SmartDefault;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
+use crate::game::platter::clear::ClearMode;
 use crate::game::platter::mesh::{
-    PlatterMainMesh, PlatterMeshes, PlatterMeshOptions, PlatterMeshOptionsObj, PlatterSegmentMesh,
+    PlatterBorderMesh, PlatterMainMesh, PlatterMeshOptions, PlatterMeshOptionsObj, PlatterMeshes,
+    PlatterSegmentMesh,
 };
+use crate::game::platter::score::PlatterScore;
 use crate::game::platter::segment::PlatterSegmentBundle;
+use crate::game::platter::spin::{PlatterSpin, SpinConfig};
 use crate::game::util::mesh::{generate_donut_vertices, generate_subdivided_donut_split_vertices};
+use crate::ui::theme::{CurrentTheme, ThemeChanged};
 use crate::util::prototype_mesh_manager::{PrototypeMesh, PrototypeMeshId};
 use crate::util::PrototypeManagerSystemParam;
 
 pub(crate) fn plugin(app: &mut App) {
     Types.register_types(app);
+    app.add_systems(
+        Update,
+        recolor_on_theme_changed.run_if(on_event::<ThemeChanged>()),
+    );
 }
 
 #[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
@@ -37,13 +46,16 @@ struct PlatterBundle {
     #[default(RigidBody::Kinematic)]
     rigid_body: RigidBody,
     angular_velocity: AngularVelocity,
+    clear_mode: ClearMode,
+    platter_score: PlatterScore,
+    platter_spin: PlatterSpin,
 }
 
 impl PlatterBundle {
     fn new(
         prototype_context: &mut PrototypeManagerSystemParam,
         platter_mesh_options: PlatterMeshOptionsObj,
-    ) -> (Self, Vec<PlatterSegmentMesh>) {
+    ) -> (Self, Vec<PlatterSegmentMesh>, PlatterBorderMesh) {
         let platter_meshes = PlatterMeshes::from(platter_mesh_options);
         if platter_meshes.main.vertices.is_empty() {
             panic!("empty vertices");
@@ -56,18 +68,28 @@ impl PlatterBundle {
             color_mesh2d_bundle,
             ..default()
         };
-        (bundle, platter_meshes.segments)
+        (bundle, platter_meshes.segments, platter_meshes.borders)
     }
     pub fn with_transform(mut self, transform: Transform) -> Self {
         self.color_mesh2d_bundle.transform = transform;
         self
     }
+    pub fn with_clear_mode(mut self, clear_mode: ClearMode) -> Self {
+        self.clear_mode = clear_mode;
+        self
+    }
+    pub fn with_spin_config(mut self, spin_config: SpinConfig) -> Self {
+        self.platter_spin = PlatterSpin::new(spin_config);
+        self
+    }
 }
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct CreatePlatterOptions {
     pub platter_mesh_options: PlatterMeshOptionsObj,
     pub transform: Transform,
+    pub clear_mode: ClearMode,
+    pub spin_config: SpinConfig,
 }
 
 pub fn create_platter<'a>(
@@ -75,9 +97,24 @@ pub fn create_platter<'a>(
     prototype_context: &mut PrototypeManagerSystemParam,
     options: CreatePlatterOptions,
 ) -> EntityCommands<'a> {
-    let (platter_bundle, segment_meshes) =
+    let (platter_bundle, segment_meshes, border_mesh) =
         PlatterBundle::new(prototype_context, options.platter_mesh_options);
-    let platter_bundle = platter_bundle.with_transform(options.transform);
+    let platter_bundle = platter_bundle
+        .with_transform(options.transform)
+        .with_clear_mode(options.clear_mode)
+        .with_spin_config(options.spin_config);
+    let borders = (!border_mesh.is_empty()).then(|| {
+        // vertex colours carry the border styles
+        let mut color_mesh2d_bundle =
+            prototype_context.get_or_create_color_mesh_2d(&border_mesh, Color::WHITE);
+        // above the segments
+        color_mesh2d_bundle.transform = Transform::from_xyz(0.0, 0.0, 1.5);
+        (
+            Name::new("PlatterBorders"),
+            border_mesh,
+            color_mesh2d_bundle,
+        )
+    });
     let segments = segment_meshes
         .into_iter()
         .map(|psm| PlatterSegmentBundle::new(prototype_context, psm));
@@ -87,9 +124,24 @@ pub fn create_platter<'a>(
             for segment in segments {
                 parent.spawn(segment);
             }
+            if let Some(borders) = borders {
+                parent.spawn(borders);
+            }
         });
     entity_commands
 }
 
 #[derive(RegisterTypeBinder)]
 pub struct Types;
+
+fn recolor_on_theme_changed(
+    mut commands: Commands,
+    mut prototype_manager_system_param: PrototypeManagerSystemParam,
+    theme: CurrentTheme,
+    platter_q: Query<Entity, With<Platter>>,
+) {
+    let material = prototype_manager_system_param.get_or_create_material(theme.get().main_color);
+    for platter in platter_q.iter() {
+        commands.entity(platter).insert(material.clone());
+    }
+}
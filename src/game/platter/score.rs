@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+}
+
+const POINTS_PER_SEGMENT: u64 = 10;
+const POINTS_PER_RING: u64 = 100;
+const LINES_PER_LEVEL: u64 = 10;
+
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct PlatterScore {
+    pub points: u64,
+    pub rings: u64,
+    pub segments: u64,
+    /// Cleared rings plus matched groups, so both clear modes level up.
+    pub lines: u64,
+    pub best_chain: usize,
+}
+
+impl PlatterScore {
+    /// Adds a single clear step, later steps in a chain are worth more.
+    pub fn add_clear(&mut self, segments: usize, rings: usize, groups: usize, chain: usize) {
+        let multiplier = chain as u64 + 1;
+        self.points +=
+            (segments as u64 * POINTS_PER_SEGMENT + rings as u64 * POINTS_PER_RING) * multiplier;
+        self.segments += segments as u64;
+        self.rings += rings as u64;
+        self.lines += (rings + groups) as u64;
+        self.best_chain = self.best_chain.max(chain);
+    }
+    /// Current level starting at 1, raised every [`LINES_PER_LEVEL`] cleared lines.
+    pub fn level(&self) -> u64 {
+        1 + self.lines / LINES_PER_LEVEL
+    }
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_counts_rings_and_groups() {
+        let mut rings = PlatterScore::default();
+        let mut matches = PlatterScore::default();
+        for _ in 0..LINES_PER_LEVEL {
+            rings.add_clear(10, 1, 0, 0);
+            matches.add_clear(4, 0, 1, 0);
+        }
+        assert_eq!(rings.level(), 2);
+        assert_eq!(matches.level(), 2);
+        assert_eq!(matches.rings, 0);
+        assert_eq!(matches.lines, LINES_PER_LEVEL);
+    }
+}
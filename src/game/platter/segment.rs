@@ -8,6 +8,7 @@ use smart_default::SmartDefault;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
+use crate::game::platter::batched::BatchedPlatterSegment;
 use crate::game::platter::mesh::PlatterSegmentMesh;
 use crate::game::platter::platter::Platter;
 use crate::game::platter::value::PlatterSegmentValue;
@@ -163,7 +164,11 @@ fn update_color(
     mut prototype_manager_system_param: PrototypeManagerSystemParam,
     segments_q: Query<
         (Entity, Ref<PlatterSegmentColor>),
-        (With<PlatterSegment>, Changed<PlatterSegmentColor>),
+        (
+            With<PlatterSegment>,
+            Without<BatchedPlatterSegment>,
+            Changed<PlatterSegmentColor>,
+        ),
     >,
 ) {
     for (entity, color) in segments_q.iter() {
@@ -14,9 +14,13 @@ pub(crate) fn plugin(app: &mut App) {
     crate::game::platter::arm::Types.register_types(app);
 }
 
-#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
 #[reflect(Component)]
-pub struct SpawnArea;
+pub struct SpawnArea {
+    /// The platter this area spawns blocks on.
+    #[default(Entity::PLACEHOLDER)]
+    pub platter: Entity,
+}
 
 #[derive(Bundle, SmartDefault, Clone)]
 pub struct SpawnAreaBundle {
@@ -33,7 +37,7 @@ impl SpawnAreaBundle {
         self.spatial_bundle.transform = transform;
         self
     }
-    pub fn new(options: PlatterMeshOptionsObj) -> Self {
+    pub fn new(platter: Entity, options: PlatterMeshOptionsObj) -> Self {
         let points = generate_donut_vertices_clamped(
             options.inner_radius,
             options.outer_radius,
@@ -44,6 +48,7 @@ impl SpawnAreaBundle {
             true,
         );
         Self {
+            spawn_area: SpawnArea { platter },
             collider_constructor: ColliderConstructor::ConvexHull { points },
             ..default()
         }
@@ -0,0 +1,159 @@
+use avian2d::prelude::{AngularVelocity, Physics};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::game_over::GameOver;
+use crate::game::platter::platter::Platter;
+use crate::game::platter::score::PlatterScore;
+
+pub(crate) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.add_event::<SpinDirectionChanged>();
+    app.add_systems(Update, apply_spin.in_set(SpinSystemSet));
+}
+
+/// Systems writing the player's spin input should run before this set.
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SpinSystemSet;
+
+/// Sent when a platter reverses its self driven spin.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct SpinDirectionChanged {
+    pub platter: Entity,
+}
+
+/// Difficulty presets controlling how fast the platter spins on its own.
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    AutoRegisterType,
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+    pub fn spin_config(self) -> SpinConfig {
+        match self {
+            Difficulty::Easy => SpinConfig {
+                base_speed: 0.1,
+                speed_per_level: 0.03,
+                max_base_speed: 1.0,
+                direction_change_secs: 0.0,
+            },
+            Difficulty::Normal => SpinConfig::default(),
+            Difficulty::Hard => SpinConfig {
+                base_speed: 0.4,
+                speed_per_level: 0.08,
+                max_base_speed: 3.0,
+                direction_change_secs: 15.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, SmartDefault, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub struct SpinConfig {
+    /// Base angular velocity at level 1 in radians per second.
+    #[default(0.2)]
+    pub base_speed: f32,
+    #[default(0.05)]
+    pub speed_per_level: f32,
+    #[default(2.0)]
+    pub max_base_speed: f32,
+    /// Seconds between automatic direction changes, `0.0` never changes direction.
+    #[default(30.0)]
+    pub direction_change_secs: f32,
+}
+
+impl SpinConfig {
+    pub fn base_speed_for_level(&self, level: u64) -> f32 {
+        let level_bonus = level.saturating_sub(1) as f32 * self.speed_per_level;
+        (self.base_speed + level_bonus).min(self.max_base_speed)
+    }
+}
+
+/// Self driven spin of a platter, the final [`AngularVelocity`] is `base * direction + input`.
+#[derive(Component, Debug, SmartDefault, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct PlatterSpin {
+    pub config: SpinConfig,
+    pub base: f32,
+    #[default(1.0)]
+    pub direction: f32,
+    /// Velocity added on top of the base spin by the player.
+    pub input: f32,
+    pub direction_elapsed: f32,
+}
+
+impl PlatterSpin {
+    pub fn new(config: SpinConfig) -> Self {
+        Self {
+            config,
+            base: config.base_speed,
+            ..default()
+        }
+    }
+    pub fn velocity(&self) -> f32 {
+        self.base * self.direction + self.input
+    }
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn apply_spin(
+    physics_time: Res<Time<Physics>>,
+    mut platter_q: Query<
+        (
+            Entity,
+            &mut PlatterSpin,
+            &PlatterScore,
+            &mut AngularVelocity,
+            Has<GameOver>,
+        ),
+        With<Platter>,
+    >,
+    mut spin_direction_changed: EventWriter<SpinDirectionChanged>,
+) {
+    for (platter, mut spin, score, mut angular_velocity, is_game_over) in platter_q.iter_mut() {
+        if is_game_over {
+            angular_velocity.0 = 0.0;
+            continue;
+        }
+        spin.base = spin.config.base_speed_for_level(score.level());
+        if spin.config.direction_change_secs > 0.0 {
+            spin.direction_elapsed += physics_time.delta_seconds();
+            if spin.direction_elapsed >= spin.config.direction_change_secs {
+                spin.direction_elapsed = 0.0;
+                spin.direction = -spin.direction;
+                spin_direction_changed.send(SpinDirectionChanged { platter });
+            }
+        }
+        angular_velocity.0 = spin.velocity();
+    }
+}
@@ -1,17 +1,18 @@
-use bevy::color::palettes::css::*;
-use bevy::color::palettes::tailwind::{CYAN_100, CYAN_200, CYAN_300};
 use bevy::prelude::*;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
-use crate::game::platter::mesh::PlatterSegmentMesh;
 use crate::game::platter::segment::PlatterSegmentColor;
-use crate::util::color_material_manager::{AssociatedColorMaterial, ColorMaterialManagerId};
+use crate::ui::theme::{CurrentTheme, ThemeChanged};
 use crate::util::ref_ext::RefExt;
 
 pub(crate) fn plugin(app: &mut App) {
     Types.register_types(app);
     app.add_systems(Update, platter_value_updated);
+    app.add_systems(
+        Update,
+        recolor_on_theme_changed.run_if(on_event::<ThemeChanged>()),
+    );
 }
 
 #[derive(Debug)]
@@ -210,17 +211,6 @@ pub enum InnerValue {
 }
 
 impl InnerValue {
-    pub fn color(&self) -> Color {
-        match self {
-            InnerValue::RedZ => RED.into(),
-            InnerValue::GreenS => GREEN.into(),
-            InnerValue::YellowO => YELLOW.into(),
-            InnerValue::PurpleT => PURPLE.into(),
-            InnerValue::BlueJ => BLUE.into(),
-            InnerValue::OrangeL => ORANGE.into(),
-            InnerValue::CyanI => CYAN_300.into(),
-        }
-    }
     pub fn shape_coordinates(&self) -> BlockGrid<bool> {
         const T: bool = true;
         const F: bool = false;
@@ -240,42 +230,75 @@ impl InnerValue {
     }
 }
 
-impl AssociatedColorMaterial for InnerValue {
-    fn get_id(&self) -> ColorMaterialManagerId {
-        self.color().get_id()
+/// Everything a single platter segment can hold.
+#[derive(Debug, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub enum CellValue {
+    Block(InnerValue),
+    /// Clears its neighbourhood when it lands or is cleared.
+    Bomb,
+    /// Can't be cleared until a neighbouring clear turns it back into a [`CellValue::Block`].
+    Locked(InnerValue),
+    /// Rises from the centre, only matches with rings or gets cleared next to a match.
+    Garbage,
+}
+
+impl CellValue {
+    pub fn inner(&self) -> Option<InnerValue> {
+        match self {
+            CellValue::Block(inner) | CellValue::Locked(inner) => Some(*inner),
+            CellValue::Bomb | CellValue::Garbage => None,
+        }
+    }
+    pub fn shape_coordinates(&self) -> BlockGrid<bool> {
+        const T: bool = true;
+        const F: bool = false;
+        match self {
+            CellValue::Block(inner) | CellValue::Locked(inner) => inner.shape_coordinates(),
+            CellValue::Bomb | CellValue::Garbage => {
+                BlockGrid::ThreeByThree([[F, T, F], [F, F, F], [F, F, F]])
+            }
+        }
     }
+}
 
-    fn get_color_material(&self) -> ColorMaterial {
-        self.color().get_color_material()
+impl From<InnerValue> for CellValue {
+    fn from(value: InnerValue) -> Self {
+        Self::Block(value)
     }
 }
 
 #[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
 #[reflect(Component)]
-pub struct PlatterSegmentValue(pub Option<InnerValue>);
+pub struct PlatterSegmentValue(pub Option<CellValue>);
 
 #[derive(RegisterTypeBinder)]
 pub struct Types;
 
 fn platter_value_updated(
+    theme: CurrentTheme,
     mut changed: Query<
-        (
-            Entity,
-            Ref<PlatterSegmentValue>,
-            &PlatterSegmentMesh,
-            Mut<PlatterSegmentColor>,
-        ),
+        (Ref<PlatterSegmentValue>, Mut<PlatterSegmentColor>),
         (Changed<PlatterSegmentValue>, With<PlatterSegmentColor>),
     >,
 ) {
-    for (entity, value, psm, mut psc) in changed.iter_mut() {
+    let theme = theme.get();
+    for (value, mut psc) in changed.iter_mut() {
         if !value.is_added_or_changed() {
             continue;
         }
-        let new_color = match value.0 {
-            None => psm.options.initial_segment_color,
-            Some(inner) => inner.color(),
-        };
-        psc.0 = new_color;
+        psc.0 = theme.cell_color(value.0);
+    }
+}
+
+fn recolor_on_theme_changed(
+    theme: CurrentTheme,
+    mut segments_q: Query<(&PlatterSegmentValue, Mut<PlatterSegmentColor>)>,
+) {
+    let theme = theme.get();
+    for (value, mut psc) in segments_q.iter_mut() {
+        let color = theme.cell_color(value.0);
+        if psc.0 != color {
+            psc.0 = color;
+        }
     }
 }
@@ -0,0 +1,213 @@
+//! Trauma based screen shake, the offset is added after following and removed before anything else
+//! touches the camera so it never leaks into the follow target. It lands after transform
+//! propagation, [`CameraSyncSet`] writes it into the `GlobalTransform` that gets drawn.
+
+use bevy::prelude::*;
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::camera::{CameraFollowSet, CameraSyncSet};
+use crate::game::platter::clear::{ClearSystemSet, SegmentsCleared};
+use crate::game::platter::controls::{PlatterInput, PlatterInputKind};
+use crate::game::platter::game_over::PlatterGameOver;
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<ScreenShakeSettings>();
+    app.add_event::<AddTrauma>();
+    app.add_systems(PreUpdate, remove_shake);
+    app.add_systems(
+        Update,
+        (trauma_from_gameplay, add_trauma)
+            .chain()
+            .after(ClearSystemSet),
+    );
+    app.add_systems(
+        PostUpdate,
+        apply_shake.after(CameraFollowSet).before(CameraSyncSet),
+    );
+}
+
+/// Accessibility setting, scales every shake, `0.0` disables it.
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct ScreenShakeSettings {
+    #[default(1.0)]
+    pub intensity: f32,
+}
+
+/// Adds trauma to every [`ScreenShake`], clamped to `1.0`.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct AddTrauma(pub f32);
+
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct ScreenShake {
+    /// `0.0..=1.0`, the shake grows with its square.
+    pub trauma: f32,
+    /// Trauma lost per second.
+    #[default(1.5)]
+    pub decay: f32,
+    #[default(12.0)]
+    pub max_offset: f32,
+    /// Radians.
+    #[default(0.05)]
+    pub max_rotation: f32,
+    #[default(15.0)]
+    pub frequency: f32,
+    applied_offset: Vec2,
+    applied_rotation: f32,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+/// Smooth pseudo random wobble in `-1.0..=1.0`, `seed` picks an independent channel.
+fn wobble(t: f32, seed: f32) -> f32 {
+    ((t + seed * 17.0).sin() + (t * 2.17 + seed * 5.3).sin() * 0.5) / 1.5
+}
+
+/// Counts segments rather than rings so match clears shake as well, a full ring of ten adds `0.15`.
+fn clear_trauma(event: &SegmentsCleared) -> f32 {
+    0.015 * event.segments.len() as f32 + 0.1 * event.chain as f32
+}
+
+fn trauma_from_gameplay(
+    mut segments_cleared: EventReader<SegmentsCleared>,
+    mut platter_input: EventReader<PlatterInput>,
+    mut platter_game_over: EventReader<PlatterGameOver>,
+    mut add_trauma: EventWriter<AddTrauma>,
+) {
+    for event in segments_cleared.read() {
+        add_trauma.send(AddTrauma(clear_trauma(event)));
+    }
+    for event in platter_input.read() {
+        if event.kind == PlatterInputKind::Drop {
+            add_trauma.send(AddTrauma(0.1));
+        }
+    }
+    for _ in platter_game_over.read() {
+        add_trauma.send(AddTrauma(0.6));
+    }
+}
+
+fn add_trauma(mut events: EventReader<AddTrauma>, mut shake_q: Query<&mut ScreenShake>) {
+    let trauma = events.read().map(|event| event.0).sum::<f32>();
+    if trauma <= 0.0 {
+        return;
+    }
+    for mut shake in shake_q.iter_mut() {
+        shake.trauma = (shake.trauma + trauma).min(1.0);
+    }
+}
+
+fn remove_shake(mut shake_q: Query<(&mut Transform, &mut ScreenShake)>) {
+    for (mut transform, mut shake) in shake_q.iter_mut() {
+        if shake.applied_offset == Vec2::ZERO && shake.applied_rotation == 0.0 {
+            continue;
+        }
+        transform.translation -= shake.applied_offset.extend(0.0);
+        transform.rotate_z(-shake.applied_rotation);
+        shake.applied_offset = Vec2::ZERO;
+        shake.applied_rotation = 0.0;
+    }
+}
+
+fn apply_shake(
+    // keeps decaying while the game is paused
+    time: Res<Time<Real>>,
+    settings: Res<ScreenShakeSettings>,
+    mut shake_q: Query<(&mut Transform, &mut ScreenShake)>,
+) {
+    for (mut transform, mut shake) in shake_q.iter_mut() {
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+        shake.trauma = (shake.trauma - shake.decay * time.delta_seconds()).max(0.0);
+        let amount = shake.trauma.powi(2) * settings.intensity.max(0.0);
+        if amount <= 0.0 {
+            continue;
+        }
+        let t = time.elapsed_seconds() * shake.frequency;
+        let offset = Vec2::new(wobble(t, 0.0), wobble(t, 1.0)) * shake.max_offset * amount;
+        let rotation = wobble(t, 2.0) * shake.max_rotation * amount;
+        transform.translation += offset.extend(0.0);
+        transform.rotate_z(rotation);
+        shake.applied_offset = offset;
+        shake.applied_rotation = rotation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::InputPlugin;
+
+    use crate::game::camera::MainCameraBundle;
+
+    use super::*;
+
+    fn setup() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+            crate::game::input::plugin,
+            crate::game::camera::plugin,
+            plugin,
+        ));
+        app.add_event::<SegmentsCleared>();
+        app.add_event::<PlatterInput>();
+        app.add_event::<PlatterGameOver>();
+        let camera = app.world_mut().spawn(MainCameraBundle::default()).id();
+        app.update();
+        (app, camera)
+    }
+
+    fn camera_translation(app: &App, camera: Entity) -> Vec3 {
+        app.world()
+            .get::<GlobalTransform>(camera)
+            .expect("expected camera GlobalTransform")
+            .translation()
+    }
+
+    #[test]
+    fn test_match_clears_add_trauma() {
+        let cleared = |segments: u32, rings, chain| SegmentsCleared {
+            platter: Entity::PLACEHOLDER,
+            segments: (0..segments).map(Entity::from_raw).collect(),
+            rings,
+            chain,
+        };
+        assert!(clear_trauma(&cleared(4, 0, 0)) > 0.0);
+        assert!(clear_trauma(&cleared(10, 1, 0)) > clear_trauma(&cleared(4, 0, 0)));
+        assert!(clear_trauma(&cleared(4, 0, 1)) > clear_trauma(&cleared(4, 0, 0)));
+    }
+
+    #[test]
+    fn test_shake_is_drawn() {
+        let (mut app, camera) = setup();
+        assert_eq!(camera_translation(&app, camera), Vec3::ZERO);
+
+        app.world_mut().send_event(AddTrauma(1.0));
+        app.update();
+        assert_ne!(
+            camera_translation(&app, camera),
+            Vec3::ZERO,
+            "expected the shake to reach the GlobalTransform"
+        );
+
+        app.world_mut()
+            .resource_mut::<ScreenShakeSettings>()
+            .intensity = 0.0;
+        app.update();
+        assert_eq!(
+            camera_translation(&app, camera),
+            Vec3::ZERO,
+            "expected the shake to be removed again"
+        );
+    }
+}
@@ -1,65 +1,166 @@
 //! Spawn the main level by triggering other observers.
 
 use avian2d::parry::utils::center;
-use avian2d::prelude::{AngularVelocity, Collider, Physics};
+use avian2d::prelude::{Collider, Physics};
 use bevy::color::palettes::css::{BLUE, DARK_GRAY, RED};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+
+use crate::game::input::{Action, ActionState};
 use crate::game::platter::arm::PlatterArm;
+use crate::game::platter::batched::PlatterRenderMode;
+use crate::game::platter::clear::{ClearMode, RaiseGarbage};
+use crate::game::platter::controls::{PlatterControls, PlatterInput, PlatterInputKind};
 use crate::game::platter::falling::{FallingSystemSet, SpawnFallingBlock};
+use crate::game::platter::game_over::GameOver;
 use crate::game::platter::mesh::PlatterMeshOptionsObj;
 use crate::game::platter::platter::{create_platter, CreatePlatterOptions, Platter};
 use crate::game::platter::spawn::{SpawnArea, SpawnAreaBundle};
-use crate::game::platter::value::InnerValue;
+use crate::game::platter::spin::{Difficulty, PlatterSpin, SpinSystemSet};
+use crate::game::platter::value::{CellValue, InnerValue};
 use crate::game::util::debug_draw::DebugDrawGizmosSystemParam;
 use crate::game::util::mesh::{
     calculate_centroid, convex_hull, generate_subdivided_donut_split_vertices, rotate_point,
 };
+use crate::game::versus::VersusPlayer;
+use crate::screen::pause::is_paused;
 use crate::screen::Screen;
+use crate::ui::theme::CurrentTheme;
 use crate::util::prototype_mesh_manager::{PrototypeMesh, PrototypeMeshId};
 use crate::util::PrototypeManagerSystemParam;
 
 pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<LevelOptions>();
     app.observe(spawn_level);
-    app.add_systems(Update, input);
-    app.add_systems(Update, test_input.before(FallingSystemSet));
+    app.add_systems(Update, input.before(SpinSystemSet).run_if(not(is_paused)));
+    app.add_systems(
+        Update,
+        test_input.before(FallingSystemSet).run_if(not(is_paused)),
+    );
 }
 
 #[derive(Event, Debug)]
 pub struct SpawnLevel;
 
+/// Options applied to the next spawned level.
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct LevelOptions {
+    pub clear_mode: ClearMode,
+    pub game_mode: GameMode,
+    pub difficulty: Difficulty,
+    pub render_mode: PlatterRenderMode,
+}
+
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    AutoRegisterType,
+)]
+pub enum GameMode {
+    #[default]
+    Single,
+    /// Two platters side by side, each controlled by its own player.
+    Versus,
+}
+
+const VERSUS_PLATTER_OFFSET: f32 = 300.0;
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
 fn spawn_level(
     _trigger: Trigger<SpawnLevel>,
     mut commands: Commands,
     mut prototype_manager_system_param: PrototypeManagerSystemParam,
     mut debug_draw_gizmos: DebugDrawGizmosSystemParam,
     mut physics: ResMut<Time<Physics>>,
+    level_options: Res<LevelOptions>,
+    theme: CurrentTheme,
 ) {
     // The only thing we have in our level is a player,
     // but add things like walls etc. here.
     // commands.trigger(SpawnPlayer);
 
     const PLATTER_RADIUS_OUTER: f32 = 250.0;
-    const PLATTER_ARM_RADIUS: f32 = PLATTER_RADIUS_OUTER * 1.15;
     const PLATTER_RADIUS_INNER: f32 = 20. / 150.0 * PLATTER_RADIUS_OUTER;
-    const PLATTER_ARM_RADIUS_CENTER: f32 = PLATTER_RADIUS_INNER * 0.9;
 
     let platter_mesh_options = PlatterMeshOptionsObj {
         inner_radius: PLATTER_RADIUS_INNER,
         outer_radius: PLATTER_RADIUS_OUTER,
         pie_cuts: 10,
         onion_layers: 20,
+        render_mode: level_options.render_mode,
+        main_color: theme.get().main_color,
+        initial_segment_color: theme.get().initial_segment_color,
         ..default()
     };
 
-    create_platter(
-        commands.spawn(StateScoped(Screen::Playing)),
-        &mut prototype_manager_system_param,
+    match level_options.game_mode {
+        GameMode::Single => {
+            spawn_player_platter(
+                &mut commands,
+                &mut prototype_manager_system_param,
+                platter_mesh_options,
+                &level_options,
+                Vec3::ZERO,
+            );
+        }
+        GameMode::Versus => {
+            for (index, x) in [-VERSUS_PLATTER_OFFSET, VERSUS_PLATTER_OFFSET]
+                .into_iter()
+                .enumerate()
+            {
+                let platter = spawn_player_platter(
+                    &mut commands,
+                    &mut prototype_manager_system_param,
+                    platter_mesh_options,
+                    &level_options,
+                    Vec3::new(x, 0.0, 0.0),
+                );
+                commands
+                    .entity(platter)
+                    .insert((VersusPlayer { index }, PlatterControls::for_player(index)));
+            }
+        }
+    }
+}
+
+/// Spawns a platter along with its arm, center cap and spawn area at `origin`.
+fn spawn_player_platter(
+    commands: &mut Commands,
+    prototype_manager_system_param: &mut PrototypeManagerSystemParam,
+    platter_mesh_options: PlatterMeshOptionsObj,
+    level_options: &LevelOptions,
+    origin: Vec3,
+) -> Entity {
+    let arm_radius = platter_mesh_options.outer_radius * 1.15;
+    let arm_radius_center = platter_mesh_options.inner_radius * 0.9;
+
+    let platter = create_platter(
+        commands.spawn((StateScoped(Screen::Playing), PlatterControls::default())),
+        prototype_manager_system_param,
         CreatePlatterOptions {
             platter_mesh_options,
-            ..default()
+            transform: Transform::from_translation(origin),
+            clear_mode: level_options.clear_mode,
+            spin_config: level_options.difficulty.spin_config(),
         },
-    );
+    )
+    .id();
 
     commands.spawn((
         PlatterArm,
@@ -67,13 +168,14 @@ fn spawn_level(
         ColorMesh2dBundle {
             mesh: prototype_manager_system_param
                 .meshes
-                .add(Rectangle::new(5.0, PLATTER_ARM_RADIUS))
+                .add(Rectangle::new(5.0, arm_radius))
                 .into(),
             material: prototype_manager_system_param.get_or_create_material(Color::from(DARK_GRAY)),
-            transform: Transform::from_xyz(0.0, PLATTER_ARM_RADIUS / 2.0, 2.0),
+            transform: Transform::from_translation(origin + Vec3::new(0.0, arm_radius / 2.0, 2.0)),
             ..default()
         },
-        Collider::rectangle(5.0, PLATTER_ARM_RADIUS),
+        Collider::rectangle(5.0, arm_radius),
+        StateScoped(Screen::Playing),
     ));
 
     commands.spawn((
@@ -81,49 +183,88 @@ fn spawn_level(
         ColorMesh2dBundle {
             mesh: prototype_manager_system_param
                 .meshes
-                .add(Circle::new(PLATTER_ARM_RADIUS_CENTER))
+                .add(Circle::new(arm_radius_center))
                 .into(),
             material: prototype_manager_system_param.get_or_create_material(Color::from(DARK_GRAY)),
+            transform: Transform::from_translation(origin),
             ..default()
         },
+        StateScoped(Screen::Playing),
+    ));
+
+    commands.spawn((
+        SpawnAreaBundle::new(platter, platter_mesh_options)
+            .with_transform(Transform::from_translation(origin)),
+        StateScoped(Screen::Playing),
     ));
 
-    commands.spawn(SpawnAreaBundle::new(platter_mesh_options));
+    platter
 }
 
 fn input(
     physics_time: Res<Time<Physics>>,
-    input: Res<ButtonInput<KeyCode>>,
-    mut platter_q: Query<(Entity, &mut AngularVelocity), With<Platter>>,
-    mut spawn: EventWriter<SpawnFallingBlock>,
+    action_state: Res<ActionState>,
+    mut platter_q: Query<(Entity, &mut PlatterSpin, &PlatterControls), Without<GameOver>>,
+    mut platter_input: EventWriter<PlatterInput>,
 ) {
-    for (_, mut angular_velocity) in platter_q.iter_mut() {
-        let right = input.pressed(KeyCode::KeyE);
-        let left = input.pressed(KeyCode::KeyQ);
+    for (platter, mut spin, controls) in platter_q.iter_mut() {
+        for (action, kind) in [
+            (controls.spin_left(), PlatterInputKind::SpinLeft),
+            (controls.spin_right(), PlatterInputKind::SpinRight),
+        ] {
+            if action_state.just_pressed(action) {
+                platter_input.send(PlatterInput { platter, kind });
+            }
+        }
+        let right = action_state.pressed(controls.spin_right());
+        let left = action_state.pressed(controls.spin_left());
         let velocity_delta = if left != right {
             10.0 * if right { 1.0 } else { -1.0 }
-        } else if angular_velocity.0 >= 1.0 || angular_velocity.0 <= -1.0 {
-            angular_velocity.0.signum() * -50.0
+        } else if spin.input >= 1.0 || spin.input <= -1.0 {
+            spin.input.signum() * -50.0
         } else {
-            angular_velocity.0 = 0.0;
+            spin.input = 0.0;
             continue;
         };
-        angular_velocity.0 += velocity_delta * physics_time.delta_seconds();
-        angular_velocity.0 = angular_velocity.0.clamp(-100.0, 100.0);
+        spin.input += velocity_delta * physics_time.delta_seconds();
+        spin.input = spin.input.clamp(-100.0, 100.0);
     }
 }
 
 fn test_input(
-    input: Res<ButtonInput<KeyCode>>,
-    platter_q: Query<Entity, With<Platter>>,
+    action_state: Res<ActionState>,
+    platter_q: Query<(Entity, &PlatterControls), (With<Platter>, Without<GameOver>)>,
     mut spawn: EventWriter<SpawnFallingBlock>,
+    mut raise_garbage: EventWriter<RaiseGarbage>,
+    mut platter_input: EventWriter<PlatterInput>,
 ) {
-    if input.just_pressed(KeyCode::Space) {
-        for entity in platter_q.iter() {
-            log::debug!("space: {entity}");
+    for (entity, controls) in platter_q.iter() {
+        let value = if action_state.just_pressed(controls.drop()) {
+            platter_input.send(PlatterInput {
+                platter: entity,
+                kind: PlatterInputKind::Drop,
+            });
+            Some(CellValue::Block(InnerValue::PurpleT))
+        } else if action_state.just_pressed(Action::SpawnBomb) {
+            Some(CellValue::Bomb)
+        } else if action_state.just_pressed(Action::SpawnLocked) {
+            Some(CellValue::Locked(InnerValue::PurpleT))
+        } else {
+            None
+        };
+        if let Some(value) = value {
+            log::debug!("spawn {value:?}: {entity}");
             spawn.send(SpawnFallingBlock {
                 platter: entity,
-                value: InnerValue::PurpleT,
+                value,
+            });
+        }
+    }
+    if action_state.just_pressed(Action::RaiseGarbage) {
+        for (entity, _) in platter_q.iter() {
+            raise_garbage.send(RaiseGarbage {
+                platter: entity,
+                layers: 1,
             });
         }
     }
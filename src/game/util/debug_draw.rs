@@ -12,8 +12,10 @@ use crate::util::string::{AnyString, AnyUniqueString};
 
 pub(crate) fn plugin(app: &mut App) {
     Types.register_types(app);
-    app.init_gizmo_group::<DebugDrawGizmos>()
-        .add_systems(Update, draw_lines);
+    app.init_gizmo_group::<DebugDrawGizmos>().add_systems(
+        Update,
+        (draw_lines, draw_primitives, expire_primitives).chain(),
+    );
 }
 
 #[derive(SystemParam)]
@@ -35,9 +37,89 @@ pub struct Point {
     color: Color,
 }
 
-#[derive(Debug, Default, Reflect, AutoRegisterType)]
+/// How long a [`DebugDrawPrimitive`] stays on screen.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub enum DebugDrawLifetime {
+    /// Until the scope is cleared.
+    #[default]
+    Forever,
+    Seconds(f32),
+    Frames(u32),
+}
+
+impl DebugDrawLifetime {
+    fn is_expired(&self) -> bool {
+        match *self {
+            DebugDrawLifetime::Forever => false,
+            DebugDrawLifetime::Seconds(seconds) => seconds <= 0.0,
+            DebugDrawLifetime::Frames(frames) => frames == 0,
+        }
+    }
+    fn tick(&mut self, delta_seconds: f32) {
+        match self {
+            DebugDrawLifetime::Forever => {}
+            DebugDrawLifetime::Seconds(seconds) => *seconds -= delta_seconds,
+            DebugDrawLifetime::Frames(frames) => *frames = frames.saturating_sub(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub enum DebugDrawShape {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    /// Arc centred on `direction_angle`, spanning `arc_angle`, both as used by [`Gizmos::arc_2d`].
+    Arc {
+        center: Vec2,
+        radius: f32,
+        direction_angle: f32,
+        arc_angle: f32,
+    },
+    Arrow {
+        from: Vec2,
+        to: Vec2,
+    },
+    Rect {
+        center: Vec2,
+        rotation: f32,
+        size: Vec2,
+    },
+    Polyline {
+        points: Vec<Vec2>,
+        closed: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, AutoRegisterType)]
+pub struct DebugDrawPrimitive {
+    shape: DebugDrawShape,
+    color: Color,
+    lifetime: DebugDrawLifetime,
+}
+
+impl DebugDrawPrimitive {
+    pub fn for_seconds(&mut self, seconds: f32) -> &mut Self {
+        self.lifetime = DebugDrawLifetime::Seconds(seconds);
+        self
+    }
+    pub fn for_frames(&mut self, frames: u32) -> &mut Self {
+        self.lifetime = DebugDrawLifetime::Frames(frames);
+        self
+    }
+    pub fn with_lifetime(&mut self, lifetime: DebugDrawLifetime) -> &mut Self {
+        self.lifetime = lifetime;
+        self
+    }
+}
+
+#[derive(Debug, SmartDefault, Reflect, AutoRegisterType)]
 pub struct DebugDrawScope {
+    #[default(true)]
+    pub visible: bool,
     line_strip: Vec<Point>,
+    primitives: Vec<DebugDrawPrimitive>,
 }
 
 impl DebugDrawScope {
@@ -51,8 +133,84 @@ impl DebugDrawScope {
         let color = color.into();
         self.line_strip.push(Point { pos: point, color });
     }
+    /// Adds a primitive that lives until the scope is cleared, chain `for_*` to expire it.
+    pub fn add(
+        &mut self,
+        shape: DebugDrawShape,
+        color: impl Into<Color>,
+    ) -> &mut DebugDrawPrimitive {
+        self.primitives.push(DebugDrawPrimitive {
+            shape,
+            color: color.into(),
+            lifetime: DebugDrawLifetime::Forever,
+        });
+        self.primitives.last_mut().expect("just pushed")
+    }
+    pub fn circle(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        color: impl Into<Color>,
+    ) -> &mut DebugDrawPrimitive {
+        self.add(DebugDrawShape::Circle { center, radius }, color)
+    }
+    pub fn arc(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        direction_angle: f32,
+        arc_angle: f32,
+        color: impl Into<Color>,
+    ) -> &mut DebugDrawPrimitive {
+        self.add(
+            DebugDrawShape::Arc {
+                center,
+                radius,
+                direction_angle,
+                arc_angle,
+            },
+            color,
+        )
+    }
+    pub fn arrow(
+        &mut self,
+        from: Vec2,
+        to: Vec2,
+        color: impl Into<Color>,
+    ) -> &mut DebugDrawPrimitive {
+        self.add(DebugDrawShape::Arrow { from, to }, color)
+    }
+    pub fn rect(
+        &mut self,
+        center: Vec2,
+        rotation: f32,
+        size: Vec2,
+        color: impl Into<Color>,
+    ) -> &mut DebugDrawPrimitive {
+        self.add(
+            DebugDrawShape::Rect {
+                center,
+                rotation,
+                size,
+            },
+            color,
+        )
+    }
+    pub fn polyline(
+        &mut self,
+        points: impl IntoIterator<Item = Vec2>,
+        closed: bool,
+        color: impl Into<Color>,
+    ) -> &mut DebugDrawPrimitive {
+        let points = points.into_iter().collect();
+        self.add(DebugDrawShape::Polyline { points, closed }, color)
+    }
+    pub fn primitive_count(&self) -> usize {
+        self.primitives.len()
+    }
     pub fn clear(&mut self) {
         self.line_strip.clear();
+        self.primitives.clear();
     }
 }
 
@@ -83,6 +241,12 @@ impl DebugDrawGizmos {
     pub fn scope(&mut self, scope: impl Into<AnyUniqueString>) -> &mut DebugDrawScope {
         self.scopes.entry(scope.into()).or_default()
     }
+    /// Scopes sorted by name, for listing them in a stable order.
+    pub fn scopes_mut(&mut self) -> impl Iterator<Item = (&AnyUniqueString, &mut DebugDrawScope)> {
+        self.scopes
+            .iter_mut()
+            .sorted_by_key(|(name, _)| name.to_string())
+    }
 }
 
 #[derive(RegisterTypeBinder)]
@@ -90,8 +254,61 @@ pub struct Types;
 
 fn draw_lines(mut debug_gizmos: Gizmos<DebugDrawGizmos>) {
     for scope in debug_gizmos.config_ext.scopes.values() {
+        if !scope.visible {
+            continue;
+        }
         for (a, b) in scope.line_strip.iter().tuple_windows() {
             debug_gizmos.line_2d(a.pos, b.pos, [a.color, b.color].avg());
         }
     }
 }
+
+fn draw_primitives(mut debug_gizmos: Gizmos<DebugDrawGizmos>) {
+    let config = debug_gizmos.config_ext;
+    let primitives = config
+        .scopes
+        .values()
+        .filter(|scope| scope.visible)
+        .flat_map(|scope| scope.primitives.iter());
+    for primitive in primitives {
+        let color = primitive.color;
+        match primitive.shape {
+            DebugDrawShape::Circle { center, radius } => {
+                debug_gizmos.circle_2d(center, radius, color);
+            }
+            DebugDrawShape::Arc {
+                center,
+                radius,
+                direction_angle,
+                arc_angle,
+            } => {
+                debug_gizmos.arc_2d(center, direction_angle, arc_angle, radius, color);
+            }
+            DebugDrawShape::Arrow { from, to } => {
+                debug_gizmos.arrow_2d(from, to, color);
+            }
+            DebugDrawShape::Rect {
+                center,
+                rotation,
+                size,
+            } => {
+                debug_gizmos.rect_2d(center, rotation, size, color);
+            }
+            DebugDrawShape::Polyline { ref points, closed } => {
+                let closing_point = points.first().filter(|_| closed);
+                debug_gizmos.linestrip_2d(points.iter().chain(closing_point).copied(), color);
+            }
+        }
+    }
+}
+
+/// Ticks lifetimes after drawing, so `Frames(1)` is drawn exactly once.
+fn expire_primitives(time: Res<Time>, mut debug_draw_gizmos: DebugDrawGizmosSystemParam) {
+    let delta_seconds = time.delta_seconds();
+    for scope in debug_draw_gizmos.get().scopes.values_mut() {
+        scope.primitives.retain_mut(|primitive| {
+            primitive.lifetime.tick(delta_seconds);
+            !primitive.lifetime.is_expired()
+        });
+    }
+}
@@ -1,7 +1,8 @@
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::PI;
 
 use bevy::math::Vec3;
-use bevy::prelude::{Mesh, Vec2};
+use bevy::prelude::{Color, ColorToComponents, Mesh, Vec2};
 use bevy::render::mesh::{Indices, PrimitiveTopology};
 use bevy::render::render_asset::RenderAssetUsages;
 use ordered_float::OrderedFloat;
@@ -87,22 +88,291 @@ pub fn close_line_strip(vertices: &mut Vec<Vec2>) {
     vertices.push(first)
 }
 
-pub fn line_strip_2d_to_mesh(mut vertices: Vec<Vec2>) -> Mesh {
-    let mut mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::RENDER_WORLD,
+/// UVs spanning the bounding box of `vertices`, used when there is no better mapping.
+pub fn planar_uvs(vertices: &[Vec2]) -> Vec<Vec2> {
+    let (min, max) = vertices.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), &vertex| (min.min(vertex), max.max(vertex)),
     );
+    let size = (max - min).max(Vec2::splat(f32::EPSILON));
+    vertices
+        .iter()
+        .map(|&vertex| (vertex - min) / size)
+        .collect()
+}
+
+/// Polar UVs matching the vertex layout of [`generate_donut_vertices_clamped`]
+/// and [`generate_subdivided_donut_split_vertices`].
+/// U runs along the angle from the start to the stop angle, V runs outward from the inner radius.
+pub fn donut_vertex_uvs(
+    inner_resolution: usize,
+    outer_resolution: usize,
+    close: bool,
+) -> Vec<Vec2> {
+    let mut uvs = Vec::with_capacity(inner_resolution + outer_resolution + 3);
+    for i in 0..=inner_resolution {
+        uvs.push(Vec2::new(i as f32 / inner_resolution as f32, 0.0));
+    }
+    for i in (0..=outer_resolution).rev() {
+        uvs.push(Vec2::new(i as f32 / outer_resolution as f32, 1.0));
+    }
+    if close {
+        uvs.push(Vec2::ZERO);
+    }
+    uvs
+}
+
+/// Only kept in the render world, so the mesh can't be outlined.
+pub fn line_strip_2d_to_mesh(vertices: Vec<Vec2>) -> Mesh {
+    let uvs = planar_uvs(&vertices);
+    line_strip_2d_to_mesh_with_uvs(vertices, uvs)
+}
+
+/// Same as [`line_strip_2d_to_mesh`] with a UV for each vertex.
+pub fn line_strip_2d_to_mesh_with_uvs(mut vertices: Vec<Vec2>, mut uvs: Vec<Vec2>) -> Mesh {
+    assert_eq!(vertices.len(), uvs.len(), "expected a uv for each vertex");
+    let len = vertices.len();
     close_line_strip(&mut vertices);
-    let indices = ear_clip_triangulate(&vertices).into_flattened();
+    if vertices.len() > len {
+        uvs.push(uvs[0]);
+    }
+    let indices = ear_clip_triangulate(&vertices);
+    triangles_to_mesh(vertices, uvs, indices, RenderAssetUsages::RENDER_WORLD)
+}
+
+/// Triangle indices for the vertex layout of [`generate_donut_vertices_clamped`]
+/// and [`generate_subdivided_donut_split_vertices`].
+/// Zips the inner and outer arcs together as a strip, so it works for any pair of resolutions.
+pub fn annular_sector_triangulate(
+    inner_resolution: usize,
+    outer_resolution: usize,
+) -> Vec<[usize; 3]> {
+    let inner = |ix: usize| ix;
+    // the outer arc is stored in reverse after the inner arc
+    let outer = |ix: usize| inner_resolution + 1 + outer_resolution - ix;
+    let mut indices = Vec::with_capacity(inner_resolution + outer_resolution);
+    let (mut inner_ix, mut outer_ix) = (0, 0);
+    while inner_ix < inner_resolution || outer_ix < outer_resolution {
+        // advance whichever arc has the closer next point
+        let advance_inner = outer_ix == outer_resolution
+            || inner_ix < inner_resolution
+                && (inner_ix + 1) * outer_resolution <= (outer_ix + 1) * inner_resolution;
+        if advance_inner {
+            indices.push([inner(inner_ix), outer(outer_ix), inner(inner_ix + 1)]);
+            inner_ix += 1;
+        } else {
+            indices.push([inner(inner_ix), outer(outer_ix), outer(outer_ix + 1)]);
+            outer_ix += 1;
+        }
+    }
+    indices
+}
+
+/// Builds a mesh for an annular sector generated by [`generate_donut_vertices_clamped`]
+/// or [`generate_subdivided_donut_split_vertices`] without going through ear clipping.
+/// Kept in the main world too, selected segments derive their outline from it.
+pub fn annular_sector_to_mesh(
+    vertices: Vec<Vec2>,
+    uvs: Vec<Vec2>,
+    inner_resolution: usize,
+    outer_resolution: usize,
+) -> Mesh {
+    assert_eq!(vertices.len(), uvs.len(), "expected a uv for each vertex");
+    assert!(
+        vertices.len() >= inner_resolution + outer_resolution + 2,
+        "vertices don't match the resolution"
+    );
+    let indices = annular_sector_triangulate(inner_resolution, outer_resolution);
+    triangles_to_mesh(
+        vertices,
+        uvs,
+        indices,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Rectangle of `width` centred on the ray at `angle`, from `inner_radius` to `outer_radius`.
+/// Vertices are counter-clockwise.
+pub fn radial_quad(inner_radius: f32, outer_radius: f32, angle: f32, width: f32) -> [Vec2; 4] {
+    let direction = Vec2::from_angle(angle);
+    let offset = direction.perp() * width / 2.0;
+    let inner = direction * inner_radius;
+    let outer = direction * outer_radius;
+    [
+        inner - offset,
+        outer - offset,
+        outer + offset,
+        inner + offset,
+    ]
+}
+
+/// Accumulates flat coloured triangle soups into a single vertex coloured mesh. The built mesh is
+/// only kept in the render world, so it can't be outlined.
+#[derive(Debug, Default, Clone)]
+pub struct ColoredMeshBuilder {
+    positions: Vec<Vec3>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl ColoredMeshBuilder {
+    pub fn push(&mut self, vertices: &[Vec2], triangles: &[[usize; 3]], color: Color) {
+        let start = self.positions.len();
+        self.positions
+            .extend(vertices.iter().map(|vertex| vertex.extend(0.0)));
+        self.colors
+            .extend(vec![color.to_linear().to_f32_array(); vertices.len()]);
+        self.indices
+            .extend(triangles.iter().flatten().map(|&ix| (start + ix) as u32));
+    }
+    pub fn build(self) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        let uvs = planar_uvs(
+            &self
+                .positions
+                .iter()
+                .map(|v| v.truncate())
+                .collect::<Vec<_>>(),
+        );
+        let normals = vec![Vec3::Z; self.positions.len()];
+        mesh.insert_indices(Indices::U32(self.indices));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh
+    }
+}
+
+/// Closed boundary loops of a triangle soup, each running counter-clockwise around the filled area
+/// so the outside is always on the right. Vertices sharing a position are merged first.
+pub fn boundary_loops(vertices: &[Vec2], triangles: &[[usize; 3]]) -> Vec<Vec<Vec2>> {
+    // seams of closed meshes repeat vertices, those edges are not a boundary
+    let mut welded = HashMap::new();
+    let canonical = vertices
+        .iter()
+        .enumerate()
+        .map(|(ix, vertex)| {
+            *welded
+                .entry((*vertex * 1e4).round().as_ivec2())
+                .or_insert(ix)
+        })
+        .collect::<Vec<_>>();
+    let mut edges = HashSet::new();
+    for &[a, b, c] in triangles {
+        let [a, b, c] = [canonical[a], canonical[b], canonical[c]];
+        let area = (vertices[b] - vertices[a]).perp_dot(vertices[c] - vertices[a]);
+        if area.abs() <= f32::EPSILON {
+            continue;
+        }
+        let [a, b, c] = if area > 0.0 { [a, b, c] } else { [a, c, b] };
+        edges.extend([(a, b), (b, c), (c, a)]);
+    }
+    let mut next = HashMap::<usize, Vec<usize>>::new();
+    for &(a, b) in &edges {
+        if !edges.contains(&(b, a)) {
+            next.entry(a).or_default().push(b);
+        }
+    }
+    let mut loops = vec![];
+    while let Some(&start) = next.keys().next() {
+        let mut ring = vec![];
+        let mut current = start;
+        while let Some(targets) = next.get_mut(&current) {
+            let target = targets.pop().expect("empty targets are removed");
+            if targets.is_empty() {
+                next.remove(&current);
+            }
+            ring.push(vertices[current]);
+            current = target;
+            if current == start {
+                break;
+            }
+        }
+        if ring.len() >= 3 {
+            loops.push(ring);
+        }
+    }
+    loops
+}
+
+/// [`boundary_loops`] of a triangle list mesh, empty for any other topology.
+pub fn mesh_boundary_loops(mesh: &Mesh) -> Vec<Vec<Vec2>> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return vec![];
+    }
+    let Some(positions) = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .and_then(|positions| positions.as_float3())
+    else {
+        return vec![];
+    };
+    let vertices = positions
+        .iter()
+        .map(|&[x, y, _]| Vec2::new(x, y))
+        .collect::<Vec<_>>();
+    let indices = match mesh.indices() {
+        Some(indices) => indices.iter().collect::<Vec<_>>(),
+        None => (0..vertices.len()).collect(),
+    };
+    let triangles = indices
+        .chunks_exact(3)
+        .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+        .collect::<Vec<_>>();
+    boundary_loops(&vertices, &triangles)
+}
+
+/// Band of `width` along the outside of loops from [`boundary_loops`] with mitred corners.
+pub fn stroke_outside(loops: &[Vec<Vec2>], width: f32) -> (Vec<Vec2>, Vec<[usize; 3]>) {
+    const MITER_LIMIT: f32 = 4.0;
+    let mut vertices = vec![];
+    let mut triangles = vec![];
+    for ring in loops {
+        let len = ring.len();
+        let start = vertices.len();
+        for ix in 0..len {
+            let prev = ring[(ix + len - 1) % len];
+            let curr = ring[ix];
+            let next = ring[(ix + 1) % len];
+            let normal_in = -(curr - prev).normalize_or_zero().perp();
+            let normal_out = -(next - curr).normalize_or_zero().perp();
+            let miter = (normal_in + normal_out)
+                .try_normalize()
+                .unwrap_or(normal_out);
+            let length = width / miter.dot(normal_out).max(1.0 / MITER_LIMIT);
+            vertices.extend([curr, curr + miter * length]);
+        }
+        for ix in 0..len {
+            let [inner, outer] = [start + ix * 2, start + ix * 2 + 1];
+            let next = (ix + 1) % len;
+            let [next_inner, next_outer] = [start + next * 2, start + next * 2 + 1];
+            triangles.extend([[inner, outer, next_outer], [inner, next_outer, next_inner]]);
+        }
+    }
+    (vertices, triangles)
+}
+
+fn triangles_to_mesh(
+    vertices: Vec<Vec2>,
+    uvs: Vec<Vec2>,
+    indices: Vec<[usize; 3]>,
+    asset_usage: RenderAssetUsages,
+) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, asset_usage);
     let vertices = vertices
         .into_iter()
         .map(|v| v.extend(0.0))
         .collect::<Vec<_>>();
     let normals = (0..vertices.len()).map(|_| Vec3::Z).collect::<Vec<_>>();
-    let uvs = (0..vertices.len()).map(|_| Vec2::ONE).collect::<Vec<_>>();
     mesh.insert_indices(Indices::U32(
-        // TODO: this is messy
-        indices.into_iter().map(|v| v as u32).collect(),
+        indices
+            .into_flattened()
+            .into_iter()
+            .map(|v| v as u32)
+            .collect(),
     ));
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
     mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
@@ -321,4 +591,68 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_donut_vertex_uvs_match_vertices() {
+        let vertices = generate_donut_vertices_clamped(1.0, 2.0, 4, 8, 0.0, PI / 2.0, true);
+        let uvs = donut_vertex_uvs(4, 8, true);
+        assert_eq!(vertices.len(), uvs.len());
+        for (vertex, uv) in vertices.iter().zip(uvs) {
+            let radius = vertex.length();
+            let angle = vertex.y.atan2(vertex.x);
+            assert!((radius - (1.0 + uv.y)).abs() < 1e-4, "{vertex:?} {uv:?}");
+            assert!((angle - uv.x * PI / 2.0).abs() < 1e-4, "{vertex:?} {uv:?}");
+        }
+    }
+
+    #[test]
+    fn test_boundary_loops_and_stroke() {
+        // two squares sharing an edge, the second one wound clockwise with duplicated vertices
+        let vertices = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ];
+        let triangles = [[0, 1, 2], [0, 2, 3], [4, 6, 5], [4, 7, 6]];
+        let loops = boundary_loops(&vertices, &triangles);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 6);
+        let (stroke_vertices, stroke_triangles) = stroke_outside(&loops, 0.5);
+        for [a, b, c] in stroke_triangles {
+            let (a, b, c) = (stroke_vertices[a], stroke_vertices[b], stroke_vertices[c]);
+            assert!((b - a).perp_dot(c - a) > 0.0);
+        }
+        for vertex in stroke_vertices {
+            assert!(vertex.x >= -0.5 && vertex.x <= 2.5 && vertex.y >= -0.5 && vertex.y <= 1.5);
+        }
+    }
+
+    #[test]
+    fn test_annular_sector_triangulate() {
+        for (inner_resolution, outer_resolution) in [(1, 1), (4, 8), (8, 3), (32, 64)] {
+            let vertices = generate_donut_vertices_clamped(
+                1.0,
+                2.0,
+                inner_resolution,
+                outer_resolution,
+                0.0,
+                PI / 3.0,
+                true,
+            );
+            let indices = annular_sector_triangulate(inner_resolution, outer_resolution);
+            assert_eq!(indices.len(), inner_resolution + outer_resolution);
+            for [a, b, c] in indices {
+                let (a, b, c) = (vertices[a], vertices[b], vertices[c]);
+                assert!(
+                    (b - a).perp_dot(c - a) > 0.0,
+                    "not counter-clockwise for {inner_resolution}x{outer_resolution}"
+                );
+            }
+        }
+    }
 }
@@ -1,12 +1,15 @@
 use bevy::ecs::query::QueryData;
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
 use bevy::sprite::Mesh2dHandle;
+use bevy::utils::HashSet;
 use smart_default::SmartDefault;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 use internal_shared::register_type_binder::RegisterTypeBinder;
 
+use crate::game::util::mesh::{mesh_boundary_loops, stroke_outside, ColoredMeshBuilder};
 use crate::util::color_material_manager::ColorMaterialManager;
 
 pub(crate) fn plugin(app: &mut App) {
@@ -14,13 +17,25 @@ pub(crate) fn plugin(app: &mut App) {
     app.add_systems(Update, (outline_changed, outline_removed).chain());
 }
 
+/// Stroke drawn around the outside of an entity's [`Mesh2dHandle`].
+///
+/// Only meshes kept in the main world, such as segments built by
+/// [`annular_sector_to_mesh`](super::mesh::annular_sector_to_mesh), can be outlined. Render-only
+/// meshes are skipped with a warning.
 #[derive(Component, Debug, SmartDefault, Reflect, AutoRegisterType)]
 #[reflect(Component)]
 pub struct Outline {
     #[default(Color::WHITE)]
     color: Color,
-    #[default(1.1)]
-    size: f32,
+    /// Stroke width in world units.
+    #[default(2.0)]
+    width: f32,
+}
+
+impl Outline {
+    pub fn new(color: Color, width: f32) -> Self {
+        Self { color, width }
+    }
 }
 
 #[derive(Component, Debug, SmartDefault, Reflect, AutoRegisterType)]
@@ -30,10 +45,13 @@ pub struct OutlineMeshMarker;
 #[derive(RegisterTypeBinder)]
 pub struct Types;
 
+/// Local z of the stroke, it only covers the outside of the mesh so it can be drawn on top.
+const OUTLINE_Z_OFFSET: f32 = 0.5;
+
 #[derive(QueryData)]
 struct OutlineProcessQueryData<'w> {
     entity: Entity,
-    transform: &'w Transform,
+    global_transform: &'w GlobalTransform,
     outline: Ref<'w, Outline>,
     mesh_handle: Ref<'w, Mesh2dHandle>,
 }
@@ -41,23 +59,45 @@ struct OutlineProcessQueryData<'w> {
 #[derive(SystemParam)]
 struct OutlineProcessSystemParams<'w, 's> {
     commands: Commands<'w, 's>,
+    meshes: ResMut<'w, Assets<Mesh>>,
     materials: ResMut<'w, Assets<ColorMaterial>>,
     color_material_manager: ResMut<'w, ColorMaterialManager>,
     child_mesh_marker_q: Query<'w, 's, (), With<OutlineMeshMarker>>,
     child_q: Query<'w, 's, &'static Children>,
 }
 
-#[derive(SystemParam)]
-struct OutlineQuerySystemParams<'w, 's> {
-    outline_changed_q: Query<'w, 's, OutlineProcessQueryData<'static>, Changed<Outline>>,
-    mesh_changed_q: Query<'w, 's, OutlineProcessQueryData<'static>, Changed<Handle<Mesh>>>,
-}
-
 fn process(
     process_params: &mut OutlineProcessSystemParams,
     query_item: OutlineProcessQueryDataItem,
 ) {
     debug!("process: {}", query_item.entity);
+    let Some(source_mesh) = process_params.meshes.get(&query_item.mesh_handle.0) else {
+        // generated meshes are added right away, a missing one was only kept in the render world
+        warn!(
+            "mesh of {} has no main world data, it can't be outlined",
+            query_item.entity
+        );
+        return;
+    };
+    if !source_mesh
+        .asset_usage
+        .contains(RenderAssetUsages::MAIN_WORLD)
+    {
+        warn!(
+            "mesh of {} is only kept in the render world, its outline won't follow changes",
+            query_item.entity
+        );
+    }
+    let scale = query_item
+        .global_transform
+        .compute_transform()
+        .scale
+        .x
+        .abs();
+    let (vertices, triangles) = stroke_outside(
+        &mesh_boundary_loops(source_mesh),
+        query_item.outline.width / scale.max(f32::EPSILON),
+    );
     let mut child = None;
     for child_entity in process_params.child_q.iter_descendants(query_item.entity) {
         if !process_params.child_mesh_marker_q.contains(child_entity) {
@@ -94,34 +134,37 @@ fn process(
         // probably clicking the OutlineMeshMarker in the inspector
         return;
     };
-    let transform =
-        Transform::from_xyz(0.0, 0.0, query_item.transform.translation.z - 1.0).with_scale(
-            Vec3::new(query_item.outline.size, query_item.outline.size, 1.0),
-        );
+    let mut builder = ColoredMeshBuilder::default();
+    // the material provides the colour
+    builder.push(&vertices, &triangles, Color::WHITE);
+    let mesh_handle = Mesh2dHandle(process_params.meshes.add(builder.build()));
     let color_material_handle = process_params
         .color_material_manager
         .get_or_create(&mut process_params.materials, query_item.outline.color);
     process_params.commands.entity(child).try_insert((
-        query_item.mesh_handle.clone(),
+        mesh_handle,
         color_material_handle,
-        transform,
+        Transform::from_xyz(0.0, 0.0, OUTLINE_Z_OFFSET),
     ));
 }
 
-fn outline_changed(mut params: OutlineProcessSystemParams, query: OutlineQuerySystemParams) {
-    fn should_process<T>(res: &Ref<T>) -> bool {
-        res.is_added() || res.is_changed()
-    }
-    for item in query.outline_changed_q.iter() {
-        debug!("process: {}", item.entity);
-        if !should_process(&item.outline) {
-            continue;
-        }
-        process(&mut params, item);
-    }
-    for item in query.mesh_changed_q.iter() {
-        debug!("process: {}", item.entity);
-        if !should_process(&item.mesh_handle) {
+fn outline_changed(
+    mut params: OutlineProcessSystemParams,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    outline_q: Query<OutlineProcessQueryData>,
+) {
+    let updated_meshes = mesh_events
+        .read()
+        .filter_map(|event| match *event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(id),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    for item in outline_q.iter() {
+        if !item.outline.is_changed()
+            && !item.mesh_handle.is_changed()
+            && !updated_meshes.contains(&item.mesh_handle.0.id())
+        {
             continue;
         }
         process(&mut params, item);
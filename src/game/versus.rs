@@ -0,0 +1,144 @@
+//! Local two-player versus rules: clears send garbage to the opponent and the
+//! last platter standing wins.
+
+use bevy::prelude::*;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::clear::{ClearSystemSet, RaiseGarbage, SegmentsCleared};
+use crate::game::platter::game_over::{GameOver, PlatterGameOver};
+use crate::game::platter::mesh::PlatterMeshOptions;
+use crate::game::platter::score::PlatterScore;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<VersusResult>();
+    app.add_systems(
+        Update,
+        (send_garbage, detect_winner)
+            .after(ClearSystemSet)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Marks a platter as belonging to a versus player.
+#[derive(Component, Debug, Default, Copy, Clone, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub struct VersusPlayer {
+    pub index: usize,
+}
+
+/// Outcome of the last finished versus match.
+#[derive(Resource, Debug, Default, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct VersusResult {
+    /// `None` when both players topped out on the same frame.
+    pub winner: Option<usize>,
+    pub scores: Vec<(usize, PlatterScore)>,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+/// Garbage layers sent for one step of a clear cascade. Match clears count a ring for every
+/// `pie_cuts` segments so they hit as hard as ring clears do.
+fn garbage_layers(event: &SegmentsCleared, pie_cuts: usize) -> usize {
+    if event.segments.is_empty() {
+        return 0;
+    }
+    let rings = event.rings.max(event.segments.len() / pie_cuts.max(1));
+    // a single ring only keeps up, clears that are part of a chain hit harder
+    (rings + event.chain).saturating_sub(1)
+}
+
+fn send_garbage(
+    mut segments_cleared: EventReader<SegmentsCleared>,
+    players_q: Query<(Entity, &PlatterMeshOptions, Has<GameOver>), With<VersusPlayer>>,
+    mut raise_garbage: EventWriter<RaiseGarbage>,
+) {
+    for event in segments_cleared.read() {
+        let Some((_, platter_mesh_options, _)) = players_q.get(event.platter).ok() else {
+            continue;
+        };
+        let layers = garbage_layers(event, platter_mesh_options.get().pie_cuts);
+        if layers == 0 {
+            continue;
+        }
+        for (opponent, _, is_game_over) in players_q.iter() {
+            if opponent == event.platter || is_game_over {
+                continue;
+            }
+            raise_garbage.send(RaiseGarbage {
+                platter: opponent,
+                layers,
+            });
+        }
+    }
+}
+
+fn detect_winner(
+    mut platter_game_over: EventReader<PlatterGameOver>,
+    players_q: Query<(Entity, &VersusPlayer, &PlatterScore, Has<GameOver>)>,
+    mut versus_result: ResMut<VersusResult>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    // `GameOver` is inserted through commands, so track this frame's losers explicitly
+    let losers = platter_game_over
+        .read()
+        .map(|event| event.platter)
+        .collect::<Vec<_>>();
+    if losers.is_empty() || players_q.is_empty() {
+        return;
+    }
+    let mut remaining = players_q
+        .iter()
+        .filter(|(entity, _, _, is_game_over)| !is_game_over && !losers.contains(entity));
+    let winner = match (remaining.next(), remaining.next()) {
+        (Some((_, player, _, _)), None) => Some(player.index),
+        (None, _) => None,
+        // more than one player is still in the match
+        (Some(_), Some(_)) => return,
+    };
+    let mut scores = players_q
+        .iter()
+        .map(|(_, player, score, _)| (player.index, *score))
+        .collect::<Vec<_>>();
+    scores.sort_by_key(|(index, _)| *index);
+    *versus_result = VersusResult { winner, scores };
+    next_screen.set(Screen::Winner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIE_CUTS: usize = 10;
+
+    fn cleared(segments: usize, rings: usize, chain: usize) -> SegmentsCleared {
+        SegmentsCleared {
+            platter: Entity::PLACEHOLDER,
+            segments: (0..segments as u32).map(Entity::from_raw).collect(),
+            rings,
+            chain,
+        }
+    }
+
+    #[test]
+    fn test_ring_clears_send_garbage() {
+        assert_eq!(garbage_layers(&cleared(10, 1, 0), PIE_CUTS), 0);
+        assert_eq!(garbage_layers(&cleared(20, 2, 0), PIE_CUTS), 1);
+        assert_eq!(garbage_layers(&cleared(10, 1, 1), PIE_CUTS), 1);
+        assert_eq!(garbage_layers(&cleared(30, 3, 2), PIE_CUTS), 4);
+    }
+
+    #[test]
+    fn test_match_clears_send_garbage() {
+        assert_eq!(garbage_layers(&cleared(0, 0, 0), PIE_CUTS), 0);
+        assert_eq!(garbage_layers(&cleared(4, 0, 0), PIE_CUTS), 0);
+        assert_eq!(garbage_layers(&cleared(4, 0, 1), PIE_CUTS), 1);
+        assert_eq!(garbage_layers(&cleared(20, 0, 0), PIE_CUTS), 1);
+        assert_eq!(garbage_layers(&cleared(12, 0, 2), PIE_CUTS), 2);
+    }
+}
@@ -0,0 +1,378 @@
+//! Local high-score tables, one per game mode and difficulty, saved to the platform data directory
+//! whenever [`HighScores`] changes.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::SystemTime;
+use serde::{Deserialize, Serialize};
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::clear::ClearSystemSet;
+use crate::game::platter::game_over::PlatterGameOver;
+use crate::game::platter::score::PlatterScore;
+use crate::game::platter::spin::Difficulty;
+use crate::game::spawn::level::{GameMode, LevelOptions};
+use crate::game::versus::{VersusPlayer, VersusResult};
+use crate::screen::Screen;
+use crate::util::storage::{load_ron_or_default, project_dirs, write_atomically};
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    let storage = HighScoreStorage::default();
+    app.insert_resource(storage.load());
+    app.insert_resource(storage);
+    app.init_resource::<RunStats>();
+    app.add_systems(OnEnter(Screen::Playing), start_run);
+    app.add_systems(OnExit(Screen::Playing), end_run);
+    app.add_systems(
+        Update,
+        finish_single_run
+            .after(ClearSystemSet)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnEnter(Screen::GameOver), record_run);
+    app.add_systems(
+        OnEnter(Screen::Winner),
+        (finish_versus_run, record_run).chain(),
+    );
+    app.add_systems(
+        Last,
+        save_high_scores
+            .run_if(resource_changed::<HighScores>.and_then(not(resource_added::<HighScores>))),
+    );
+}
+
+/// Entries kept in each table.
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// Used when the name is left empty.
+pub const DEFAULT_NAME: &str = "Player";
+
+/// Longest name accepted for an entry, in characters.
+pub const MAX_NAME_CHARS: usize = 12;
+
+/// Each game mode and difficulty keeps its own table.
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    AutoRegisterType,
+)]
+pub struct HighScoreCategory {
+    pub game_mode: GameMode,
+    pub difficulty: Difficulty,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u64,
+    /// See [`PlatterScore::lines`].
+    pub lines: u64,
+    pub level: u64,
+    /// Seconds played, without the time spent paused.
+    pub duration: f32,
+    /// Seconds since the unix epoch.
+    pub date: u64,
+}
+
+impl HighScoreEntry {
+    fn new(score: &PlatterScore, duration: Duration) -> Self {
+        Self {
+            name: DEFAULT_NAME.to_string(),
+            score: score.points,
+            lines: score.lines,
+            level: score.level(),
+            duration: duration.as_secs_f32(),
+            date: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since_epoch| since_epoch.as_secs()),
+        }
+    }
+    pub fn formatted_duration(&self) -> String {
+        format_duration(self.duration)
+    }
+    /// `yyyy-mm-dd` in UTC.
+    pub fn formatted_date(&self) -> String {
+        // days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let days = (self.date / 86_400) as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// `m:ss`
+pub fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighScores {
+    /// Name of the last entry, offered again for the next one.
+    pub last_name: Option<String>,
+    tables: BTreeMap<HighScoreCategory, Vec<HighScoreEntry>>,
+}
+
+impl HighScores {
+    /// Best first.
+    pub fn table(&self, category: HighScoreCategory) -> &[HighScoreEntry] {
+        self.tables.get(&category).map_or(&[], Vec::as_slice)
+    }
+    /// Returns the rank of the new entry, or `None` if it didn't make the table.
+    /// Ties rank below the entries that were there first.
+    pub fn insert(&mut self, category: HighScoreCategory, entry: HighScoreEntry) -> Option<usize> {
+        if entry.score == 0 {
+            return None;
+        }
+        let rank = self
+            .table(category)
+            .partition_point(|other| other.score >= entry.score);
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        let table = self.tables.entry(category).or_default();
+        table.insert(rank, entry);
+        table.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+    /// Renames the entry at `rank` and remembers the name for the next entry.
+    pub fn set_name(&mut self, category: HighScoreCategory, rank: usize, name: &str) {
+        let name = name.trim();
+        let name = if name.is_empty() { DEFAULT_NAME } else { name };
+        if let Some(entry) = self
+            .tables
+            .get_mut(&category)
+            .and_then(|table| table.get_mut(rank))
+        {
+            entry.name = name.to_string();
+        }
+        self.last_name = Some(name.to_string());
+    }
+}
+
+/// Where [`HighScores`] are stored, `None` where there's no file system (wasm).
+#[derive(Resource, Debug, Clone)]
+pub struct HighScoreStorage {
+    path: Option<PathBuf>,
+}
+
+impl Default for HighScoreStorage {
+    fn default() -> Self {
+        let path = project_dirs().map(|dirs| dirs.data_dir().join("high_scores.ron"));
+        Self { path }
+    }
+}
+
+impl HighScoreStorage {
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+    /// Empty tables without a readable file, see [`load_ron_or_default`].
+    pub fn load(&self) -> HighScores {
+        load_ron_or_default(self.path.as_deref(), ron::from_str)
+    }
+    pub fn save(&self, high_scores: &HighScores) -> io::Result<()> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+        let ron = ron::ser::to_string_pretty(high_scores, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        write_atomically(path, &ron)
+    }
+}
+
+/// Tracks the current run so its result can be recorded once it ends.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct RunStats {
+    pub category: HighScoreCategory,
+    started: Duration,
+    /// Virtual time played, which stops while paused.
+    pub duration: Duration,
+    /// Set when the run ended with a result, quitting from the pause menu leaves it `None`.
+    pub score: Option<PlatterScore>,
+}
+
+/// The entry the last run added to [`HighScores`], while its name is being entered.
+#[derive(Resource, Debug, Clone)]
+pub struct NewHighScore {
+    pub category: HighScoreCategory,
+    pub rank: usize,
+    pub name: String,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn start_run(
+    time: Res<Time<Virtual>>,
+    level_options: Res<LevelOptions>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    *run_stats = RunStats {
+        category: HighScoreCategory {
+            game_mode: level_options.game_mode,
+            difficulty: level_options.difficulty,
+        },
+        started: time.elapsed(),
+        ..default()
+    };
+}
+
+fn end_run(time: Res<Time<Virtual>>, mut run_stats: ResMut<RunStats>) {
+    run_stats.duration = time.elapsed().saturating_sub(run_stats.started);
+}
+
+fn finish_single_run(
+    mut platter_game_over: EventReader<PlatterGameOver>,
+    platter_q: Query<&PlatterScore, Without<VersusPlayer>>,
+    mut run_stats: ResMut<RunStats>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    for event in platter_game_over.read() {
+        let Ok(score) = platter_q.get(event.platter) else {
+            continue;
+        };
+        run_stats.score = Some(*score);
+        next_screen.set(Screen::GameOver);
+    }
+}
+
+fn finish_versus_run(versus_result: Res<VersusResult>, mut run_stats: ResMut<RunStats>) {
+    // only the winner gets an entry, a draw has none
+    run_stats.score = versus_result.winner.and_then(|winner| {
+        versus_result
+            .scores
+            .iter()
+            .find(|(index, _)| *index == winner)
+            .map(|(_, score)| *score)
+    });
+}
+
+/// Adds the finished run to [`HighScores`], inserting [`NewHighScore`] if it made the table.
+pub fn record_run(
+    mut commands: Commands,
+    run_stats: Res<RunStats>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    commands.remove_resource::<NewHighScore>();
+    let Some(score) = run_stats.score else {
+        return;
+    };
+    let mut entry = HighScoreEntry::new(&score, run_stats.duration);
+    if let Some(name) = &high_scores.last_name {
+        entry.name.clone_from(name);
+    }
+    let name = entry.name.clone();
+    let Some(rank) = high_scores.insert(run_stats.category, entry) else {
+        return;
+    };
+    log::debug!("new high score at rank {rank} in {:?}", run_stats.category);
+    commands.insert_resource(NewHighScore {
+        category: run_stats.category,
+        rank,
+        name,
+    });
+}
+
+fn save_high_scores(high_scores: Res<HighScores>, storage: Res<HighScoreStorage>) {
+    if let Err(err) = storage.save(&high_scores) {
+        log::warn!("failed to save high scores: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::storage::test_support::temp_file_path;
+
+    use super::*;
+
+    fn entry(score: u64) -> HighScoreEntry {
+        HighScoreEntry {
+            name: score.to_string(),
+            score,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_insert_keeps_top_entries() {
+        let category = HighScoreCategory::default();
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_HIGH_SCORES as u64 {
+            high_scores.insert(category, entry(score * 10));
+        }
+        assert_eq!(high_scores.insert(category, entry(5)), None);
+        assert_eq!(high_scores.insert(category, entry(0)), None);
+        // ties rank below the older entry
+        assert_eq!(high_scores.insert(category, entry(90)), Some(2));
+        let table = high_scores.table(category);
+        assert_eq!(table.len(), MAX_HIGH_SCORES);
+        assert_eq!(table[0].score, 100);
+        assert_eq!(table[MAX_HIGH_SCORES - 1].score, 20);
+        // other categories have their own table
+        let versus = HighScoreCategory {
+            game_mode: GameMode::Versus,
+            ..default()
+        };
+        assert!(high_scores.table(versus).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let storage = HighScoreStorage::at(temp_file_path("high_scores_save_and_load.ron"));
+        let mut high_scores = HighScores::default();
+        let category = HighScoreCategory {
+            game_mode: GameMode::Single,
+            difficulty: Difficulty::Hard,
+        };
+        let rank = high_scores.insert(category, entry(120)).unwrap();
+        high_scores.set_name(category, rank, "  ");
+        storage.save(&high_scores).unwrap();
+        let loaded = storage.load();
+        assert_eq!(loaded, high_scores);
+        assert_eq!(loaded.table(category)[0].name, DEFAULT_NAME);
+    }
+
+    #[test]
+    fn test_formatting() {
+        let entry = HighScoreEntry {
+            duration: 125.7,
+            date: 1_700_000_000,
+            ..default()
+        };
+        assert_eq!(entry.formatted_duration(), "2:05");
+        assert_eq!(entry.formatted_date(), "2023-11-14");
+        assert_eq!(HighScoreEntry::default().formatted_date(), "1970-01-01");
+    }
+}
@@ -11,12 +11,15 @@ use bevy_inspector_egui::bevy_egui::EguiPlugin;
 use bevy_mod_picking::DefaultPickingPlugins;
 use transform_gizmo_bevy::TransformGizmoPlugin;
 
-use crate::game::camera::MainCameraBundle;
+use crate::game::camera::{FollowingCamera, MainCameraBundle};
+use crate::settings::SettingsStorage;
 
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod game;
+mod high_scores;
 mod screen;
+mod settings;
 mod ui;
 mod util;
 
@@ -30,6 +33,10 @@ impl Plugin for AppPlugin {
             (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
         );
 
+        // Loaded before the Bevy plugins, some settings are only read when they're built.
+        let settings_storage = SettingsStorage::default();
+        let settings = settings_storage.load();
+
         // Spawn the main camera.
         app.add_systems(Startup, spawn_camera);
 
@@ -51,6 +58,7 @@ impl Plugin for AppPlugin {
                         canvas: Some("#bevy".to_string()),
                         fit_canvas_to_parent: true,
                         prevent_default_event_handling: true,
+                        mode: settings.window_mode,
                         ..default()
                     }
                     .into(),
@@ -58,7 +66,7 @@ impl Plugin for AppPlugin {
                 })
                 .set(AudioPlugin {
                     global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
+                        volume: Volume::new(settings.master_volume),
                     },
                     ..default()
                 }),
@@ -87,6 +95,14 @@ impl Plugin for AppPlugin {
             ui::plugin,
         ));
 
+        // Apply the loaded settings and save them when they change.
+        app.insert_resource(settings_storage);
+        app.insert_resource(settings);
+        app.add_plugins(settings::plugin);
+
+        // Load the high scores and record finished runs.
+        app.add_plugins(high_scores::plugin);
+
         // Enable dev tools for dev builds.
         #[cfg(feature = "dev")]
         app.add_plugins(dev_tools::plugin);
@@ -107,5 +123,8 @@ enum AppSet {
 }
 
 fn spawn_camera(mut commands: Commands) {
-    commands.spawn(MainCameraBundle::default());
+    commands.spawn(
+        MainCameraBundle::default()
+            .with_following_camera(FollowingCamera::smooth().with_dead_zone(10.0)),
+    );
 }
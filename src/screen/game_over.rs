@@ -0,0 +1,76 @@
+//! The screen shown once a single player run is over.
+
+use bevy::prelude::*;
+
+use crate::{
+    high_scores::{format_duration, record_run, NewHighScore, RunStats},
+    ui::prelude::*,
+};
+
+use super::{name_entry::spawn_name_entry, Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::GameOver), enter_game_over.after(record_run));
+
+    app.register_type::<GameOverAction>();
+    app.add_systems(
+        Update,
+        handle_game_over_action.run_if(in_state(Screen::GameOver)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum GameOverAction {
+    Retry,
+    HighScores,
+    Title,
+}
+
+fn enter_game_over(
+    mut commands: Commands,
+    run_stats: Res<RunStats>,
+    new_high_score: Option<Res<NewHighScore>>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::GameOver))
+        .with_children(|children| {
+            children.header("Game Over");
+            let score = run_stats.score.unwrap_or_default();
+            children.label(format!(
+                "{} points, {} lines, level {}",
+                score.points,
+                score.lines,
+                score.level()
+            ));
+            children.label(format!(
+                "Time {}",
+                format_duration(run_stats.duration.as_secs_f32())
+            ));
+            if let Some(new_high_score) = &new_high_score {
+                spawn_name_entry(children, new_high_score);
+            }
+
+            children.button("Retry").insert(GameOverAction::Retry);
+            children
+                .button("High Scores")
+                .insert(GameOverAction::HighScores);
+            children.button("Title").insert(GameOverAction::Title);
+        });
+}
+
+fn handle_game_over_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&GameOverAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                GameOverAction::Retry => next_screen.set(Screen::Playing),
+                GameOverAction::HighScores => next_screen.set(Screen::HighScores),
+                GameOverAction::Title => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
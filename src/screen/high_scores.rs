@@ -0,0 +1,126 @@
+//! A screen listing the high scores of each game mode and difficulty.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        platter::spin::Difficulty,
+        spawn::level::{GameMode, LevelOptions},
+    },
+    high_scores::{HighScoreCategory, HighScores},
+    ui::prelude::*,
+};
+
+use super::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ShownCategory>();
+    app.add_systems(OnEnter(Screen::HighScores), show_last_played);
+
+    app.register_type::<HighScoresAction>();
+    app.add_systems(
+        Update,
+        (
+            handle_high_scores_action,
+            refresh_high_scores.run_if(resource_changed::<ShownCategory>),
+        )
+            .chain()
+            .run_if(in_state(Screen::HighScores)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum HighScoresAction {
+    GameMode,
+    Difficulty,
+    Back,
+}
+
+/// The table on screen, switched with the game mode and difficulty buttons.
+#[derive(Resource, Debug, Default)]
+struct ShownCategory(HighScoreCategory);
+
+/// Root of the table, respawned when another category is shown.
+#[derive(Component)]
+struct HighScoresMenu;
+
+fn show_last_played(level_options: Res<LevelOptions>, mut shown_category: ResMut<ShownCategory>) {
+    // always marks it changed so the first refresh spawns the table
+    shown_category.0 = HighScoreCategory {
+        game_mode: level_options.game_mode,
+        difficulty: level_options.difficulty,
+    };
+}
+
+fn refresh_high_scores(
+    mut commands: Commands,
+    shown_category: Res<ShownCategory>,
+    high_scores: Res<HighScores>,
+    menu_query: Query<Entity, With<HighScoresMenu>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    let category = shown_category.0;
+    commands
+        .ui_root()
+        .insert((HighScoresMenu, StateScoped(Screen::HighScores)))
+        .with_children(|children| {
+            children.header("High Scores");
+            children
+                .button(format!("{:?}", category.game_mode))
+                .insert(HighScoresAction::GameMode);
+            children
+                .button(format!("{:?}", category.difficulty))
+                .insert(HighScoresAction::Difficulty);
+
+            let table = high_scores.table(category);
+            if table.is_empty() {
+                children.label("No scores yet");
+            }
+            for (rank, entry) in table.iter().enumerate() {
+                children.label(format!(
+                    "{}. {}  {}  {} lines  level {}  {}  {}",
+                    rank + 1,
+                    entry.name,
+                    entry.score,
+                    entry.lines,
+                    entry.level,
+                    entry.formatted_duration(),
+                    entry.formatted_date(),
+                ));
+            }
+
+            children.button("Back").insert(HighScoresAction::Back);
+        });
+}
+
+fn handle_high_scores_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut shown_category: ResMut<ShownCategory>,
+    mut button_query: InteractionQuery<&HighScoresAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                HighScoresAction::GameMode => {
+                    let category = &mut shown_category.0;
+                    category.game_mode = match category.game_mode {
+                        GameMode::Single => GameMode::Versus,
+                        GameMode::Versus => GameMode::Single,
+                    };
+                }
+                HighScoresAction::Difficulty => {
+                    let category = &mut shown_category.0;
+                    category.difficulty = match category.difficulty {
+                        Difficulty::Easy => Difficulty::Normal,
+                        Difficulty::Normal => Difficulty::Hard,
+                        Difficulty::Hard => Difficulty::Easy,
+                    };
+                }
+                HighScoresAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
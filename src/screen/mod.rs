@@ -4,10 +4,15 @@ use bevy::prelude::*;
 
 mod before_playing;
 mod credits;
+mod game_over;
+mod high_scores;
 mod loading;
+mod name_entry;
+pub mod pause;
 mod playing;
 mod splash;
 mod title;
+mod winner;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
@@ -20,6 +25,11 @@ pub(super) fn plugin(app: &mut App) {
         credits::plugin,
         before_playing::plugin,
         playing::plugin,
+        pause::plugin,
+        winner::plugin,
+        game_over::plugin,
+        high_scores::plugin,
+        name_entry::plugin,
     ));
 }
 
@@ -31,6 +41,9 @@ pub enum Screen {
     Loading,
     Title,
     Credits,
+    HighScores,
     BeforePlaying,
     Playing,
+    Winner,
+    GameOver,
 }
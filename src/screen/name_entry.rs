@@ -0,0 +1,120 @@
+//! Typing a name for a [`NewHighScore`] on the screens shown after a run.
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    high_scores::{HighScores, NewHighScore, MAX_NAME_CHARS},
+    ui::prelude::*,
+};
+
+use super::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            type_name.run_if(resource_exists::<NewHighScore>),
+            update_name_text,
+        )
+            .chain()
+            .run_if(in_state(Screen::GameOver).or_else(in_state(Screen::Winner))),
+    );
+    // leaving without pressing enter keeps the name typed so far
+    app.add_systems(OnExit(Screen::GameOver), save_name);
+    app.add_systems(OnExit(Screen::Winner), save_name);
+}
+
+#[derive(Component)]
+struct NameEntryText;
+
+/// Spawns the labels for entering the name of `new_high_score`.
+pub(super) fn spawn_name_entry(children: &mut ChildBuilder, new_high_score: &NewHighScore) {
+    children.header(format!("New high score! #{}", new_high_score.rank + 1));
+    children.label("").insert(NameEntryText);
+    children.label("Type your name, Enter to save");
+}
+
+fn type_name(
+    mut commands: Commands,
+    mut keyboard_input: EventReader<KeyboardInput>,
+    mut new_high_score: ResMut<NewHighScore>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    for event in keyboard_input.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        if event.logical_key == Key::Enter {
+            commit_name(&mut commands, &new_high_score, &mut high_scores);
+            return;
+        }
+        let name = &mut new_high_score.name;
+        match &event.logical_key {
+            Key::Backspace => {
+                name.pop();
+            }
+            Key::Space => name.push(' '),
+            Key::Character(text) => name.extend(text.chars().filter(|c| !c.is_control())),
+            _ => continue,
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            *name = name.chars().take(MAX_NAME_CHARS).collect();
+        }
+    }
+}
+
+fn update_name_text(
+    new_high_score: Option<Res<NewHighScore>>,
+    high_scores: Res<HighScores>,
+    text_q: Query<&Children, With<NameEntryText>>,
+    mut label_q: Query<&mut Text>,
+) {
+    let value = match &new_high_score {
+        Some(new_high_score) => format!("Name: {}_", new_high_score.name),
+        None => format!(
+            "Saved as {}",
+            high_scores.last_name.as_deref().unwrap_or_default()
+        ),
+    };
+    for children in &text_q {
+        let mut texts = label_q.iter_many_mut(children.iter());
+        while let Some(mut text) = texts.fetch_next() {
+            let Some(section) = text.sections.first_mut() else {
+                continue;
+            };
+            if section.value != value {
+                section.value.clone_from(&value);
+            }
+        }
+    }
+}
+
+fn save_name(
+    mut commands: Commands,
+    new_high_score: Option<Res<NewHighScore>>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let Some(new_high_score) = new_high_score else {
+        return;
+    };
+    commit_name(&mut commands, &new_high_score, &mut high_scores);
+}
+
+fn commit_name(
+    commands: &mut Commands,
+    new_high_score: &NewHighScore,
+    high_scores: &mut HighScores,
+) {
+    high_scores.set_name(
+        new_high_score.category,
+        new_high_score.rank,
+        &new_high_score.name,
+    );
+    commands.remove_resource::<NewHighScore>();
+}
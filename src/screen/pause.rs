@@ -0,0 +1,233 @@
+//! Pause overlay on top of [`Screen::Playing`], the level stays alive underneath while virtual and
+//! physics time are stopped.
+
+use avian2d::prelude::{Physics, PhysicsTime};
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::*,
+    window::{WindowFocused, WindowMode},
+};
+
+use crate::{
+    game::{
+        input::{Action, ActionState},
+        platter::{clear::ClearSystemSet, falling::FallingSystemSet, spin::SpinSystemSet},
+    },
+    settings::Settings,
+    ui::prelude::*,
+};
+
+use super::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_sub_state::<PlayingState>();
+    app.enable_state_scoped_entities::<PlayingState>();
+
+    // leaving `Running` for the menus pauses, leaving `Playing` from any of them unpauses
+    app.add_systems(OnExit(PlayingState::Running), pause_time);
+    app.add_systems(OnEnter(PlayingState::Running), unpause_time);
+    app.add_systems(OnExit(Screen::Playing), unpause_time);
+
+    // the level keeps its state but doesn't advance while a menu is open
+    app.configure_sets(
+        Update,
+        (FallingSystemSet, ClearSystemSet, SpinSystemSet).run_if(not(is_paused)),
+    );
+
+    app.add_systems(OnEnter(PlayingState::Paused), enter_paused);
+    app.add_systems(OnEnter(PlayingState::Settings), enter_settings);
+
+    app.register_type::<PauseAction>();
+    app.add_systems(
+        Update,
+        (
+            toggle_pause,
+            pause_on_focus_lost.run_if(in_state(PlayingState::Running)),
+            handle_pause_action,
+            refresh_settings
+                .run_if(in_state(PlayingState::Settings).and_then(resource_changed::<Settings>)),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum PlayingState {
+    #[default]
+    Running,
+    Paused,
+    Settings,
+}
+
+/// True while a menu is open over the level, gameplay systems should `run_if(not(is_paused))`.
+pub fn is_paused(playing_state: Option<Res<State<PlayingState>>>) -> bool {
+    playing_state.is_some_and(|state| *state.get() != PlayingState::Running)
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    Restart,
+    Settings,
+    QuitToTitle,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Fullscreen,
+    Theme,
+    Glyphs,
+    Back,
+}
+
+/// Root of the settings menu, respawned when the settings change to update the button labels.
+#[derive(Component)]
+struct SettingsMenu;
+
+fn pause_time(mut virtual_time: ResMut<Time<Virtual>>, mut physics_time: ResMut<Time<Physics>>) {
+    virtual_time.pause();
+    physics_time.pause();
+}
+
+fn unpause_time(mut virtual_time: ResMut<Time<Virtual>>, mut physics_time: ResMut<Time<Physics>>) {
+    virtual_time.unpause();
+    physics_time.unpause();
+}
+
+fn dimmed_root<'a>(commands: &'a mut Commands) -> EntityCommands<'a> {
+    let mut root = commands.ui_root();
+    root.insert(BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)));
+    root
+}
+
+fn enter_paused(mut commands: Commands) {
+    dimmed_root(&mut commands)
+        .insert(StateScoped(PlayingState::Paused))
+        .with_children(|children| {
+            children.header("Paused");
+            children.button("Resume").insert(PauseAction::Resume);
+            children.button("Restart").insert(PauseAction::Restart);
+            children.button("Settings").insert(PauseAction::Settings);
+            children.button("Quit").insert(PauseAction::QuitToTitle);
+        });
+}
+
+fn enter_settings(mut commands: Commands, settings: Res<Settings>) {
+    let percent = |volume: f32| (volume * 100.0).round();
+    let fullscreen = if settings.window_mode == WindowMode::Windowed {
+        "Off"
+    } else {
+        "On"
+    };
+    dimmed_root(&mut commands)
+        .insert((SettingsMenu, StateScoped(PlayingState::Settings)))
+        .with_children(|children| {
+            children.header("Settings");
+            children
+                .button(format!("Volume {}%", percent(settings.master_volume)))
+                .insert(PauseAction::MasterVolume);
+            children
+                .button(format!("Music {}%", percent(settings.music_volume)))
+                .insert(PauseAction::MusicVolume);
+            children
+                .button(format!("Effects {}%", percent(settings.sfx_volume)))
+                .insert(PauseAction::SfxVolume);
+            children
+                .button(format!("Fullscreen {fullscreen}"))
+                .insert(PauseAction::Fullscreen);
+            children
+                .button(format!("Theme {:?}", settings.theme))
+                .insert(PauseAction::Theme);
+            children
+                .button(if settings.glyphs {
+                    "Glyphs On"
+                } else {
+                    "Glyphs Off"
+                })
+                .insert(PauseAction::Glyphs);
+            children.button("Back").insert(PauseAction::Back);
+        });
+}
+
+fn refresh_settings(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    menu_query: Query<Entity, With<SettingsMenu>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    enter_settings(commands, settings);
+}
+
+/// Cycles `0% -> 25% -> .. -> 100% -> 0%`.
+fn next_volume(volume: f32) -> f32 {
+    [0.25, 0.5, 0.75, 1.0]
+        .into_iter()
+        .find(|&step| step > volume + 0.01)
+        .unwrap_or(0.0)
+}
+
+fn toggle_pause(
+    action_state: Res<ActionState>,
+    playing_state: Option<Res<State<PlayingState>>>,
+    mut next_playing_state: ResMut<NextState<PlayingState>>,
+) {
+    if !action_state.just_pressed(Action::Pause) {
+        return;
+    }
+    let Some(playing_state) = playing_state else {
+        return;
+    };
+    next_playing_state.set(match playing_state.get() {
+        PlayingState::Running => PlayingState::Paused,
+        PlayingState::Paused => PlayingState::Running,
+        PlayingState::Settings => PlayingState::Paused,
+    });
+}
+
+fn pause_on_focus_lost(
+    mut window_focused: EventReader<WindowFocused>,
+    mut next_playing_state: ResMut<NextState<PlayingState>>,
+) {
+    if window_focused.read().any(|event| !event.focused) {
+        next_playing_state.set(PlayingState::Paused);
+    }
+}
+
+fn handle_pause_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_playing_state: ResMut<NextState<PlayingState>>,
+    mut settings: ResMut<Settings>,
+    mut button_query: InteractionQuery<&PauseAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                PauseAction::Resume => next_playing_state.set(PlayingState::Running),
+                // passes through `BeforePlaying` so the level is despawned and spawned again
+                PauseAction::Restart => next_screen.set(Screen::BeforePlaying),
+                PauseAction::Settings => next_playing_state.set(PlayingState::Settings),
+                PauseAction::QuitToTitle => next_screen.set(Screen::Title),
+                PauseAction::MasterVolume => {
+                    settings.master_volume = next_volume(settings.master_volume);
+                }
+                PauseAction::MusicVolume => {
+                    settings.music_volume = next_volume(settings.music_volume);
+                }
+                PauseAction::SfxVolume => settings.sfx_volume = next_volume(settings.sfx_volume),
+                PauseAction::Fullscreen => {
+                    settings.window_mode = if settings.window_mode == WindowMode::Windowed {
+                        WindowMode::BorderlessFullscreen
+                    } else {
+                        WindowMode::Windowed
+                    };
+                }
+                PauseAction::Theme => settings.theme = settings.theme.next(),
+                PauseAction::Glyphs => settings.glyphs = !settings.glyphs,
+                PauseAction::Back => next_playing_state.set(PlayingState::Paused),
+            }
+        }
+    }
+}
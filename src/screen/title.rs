@@ -2,7 +2,11 @@
 
 use bevy::prelude::*;
 
-use crate::ui::prelude::*;
+use crate::{
+    game::spawn::level::{GameMode, LevelOptions},
+    settings::Settings,
+    ui::prelude::*,
+};
 
 use super::Screen;
 
@@ -10,26 +14,50 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), enter_title);
 
     app.register_type::<TitleAction>();
-    app.add_systems(Update, handle_title_action.run_if(in_state(Screen::Title)));
+    app.add_systems(
+        Update,
+        (
+            handle_title_action,
+            update_difficulty_label.run_if(resource_changed::<LevelOptions>),
+        )
+            .chain()
+            .run_if(in_state(Screen::Title)),
+    );
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 enum TitleAction {
     Play,
+    Versus,
+    /// Cycles the difficulty of the next game.
+    Difficulty,
+    HighScores,
     Credits,
+    Theme,
+    /// Toggles the colour-blind glyph overlay.
+    Glyphs,
     /// Exit doesn't work well with embedded applications.
     #[cfg(not(target_family = "wasm"))]
     Exit,
 }
 
-fn enter_title(mut commands: Commands) {
+fn enter_title(mut commands: Commands, level_options: Res<LevelOptions>) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Title))
         .with_children(|children| {
             children.button("Play").insert(TitleAction::Play);
+            children.button("Versus").insert(TitleAction::Versus);
+            children
+                .button(format!("{:?}", level_options.difficulty))
+                .insert(TitleAction::Difficulty);
+            children
+                .button("High Scores")
+                .insert(TitleAction::HighScores);
             children.button("Credits").insert(TitleAction::Credits);
+            children.button("Theme").insert(TitleAction::Theme);
+            children.button("Glyphs").insert(TitleAction::Glyphs);
 
             #[cfg(not(target_family = "wasm"))]
             children.button("Exit").insert(TitleAction::Exit);
@@ -38,14 +66,29 @@ fn enter_title(mut commands: Commands) {
 
 fn handle_title_action(
     mut next_screen: ResMut<NextState<Screen>>,
+    mut level_options: ResMut<LevelOptions>,
+    mut settings: ResMut<Settings>,
     mut button_query: InteractionQuery<&TitleAction>,
     #[cfg(not(target_family = "wasm"))] mut app_exit: EventWriter<AppExit>,
 ) {
     for (interaction, action) in &mut button_query {
         if matches!(interaction, Interaction::Pressed) {
             match action {
-                TitleAction::Play => next_screen.set(Screen::Playing),
+                TitleAction::Play => {
+                    level_options.game_mode = GameMode::Single;
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Versus => {
+                    level_options.game_mode = GameMode::Versus;
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Difficulty => {
+                    level_options.difficulty = level_options.difficulty.next();
+                }
+                TitleAction::HighScores => next_screen.set(Screen::HighScores),
                 TitleAction::Credits => next_screen.set(Screen::Credits),
+                TitleAction::Theme => settings.theme = settings.theme.next(),
+                TitleAction::Glyphs => settings.glyphs = !settings.glyphs,
 
                 #[cfg(not(target_family = "wasm"))]
                 TitleAction::Exit => {
@@ -55,3 +98,19 @@ fn handle_title_action(
         }
     }
 }
+
+fn update_difficulty_label(
+    level_options: Res<LevelOptions>,
+    button_query: Query<(&TitleAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (action, children) in &button_query {
+        if *action != TitleAction::Difficulty {
+            continue;
+        }
+        let mut iter = text_query.iter_many_mut(children);
+        while let Some(mut text) = iter.fetch_next() {
+            text.sections[0].value = format!("{:?}", level_options.difficulty);
+        }
+    }
+}
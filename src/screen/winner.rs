@@ -0,0 +1,72 @@
+//! The screen shown once a versus match has a winner.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::versus::VersusResult,
+    high_scores::{record_run, NewHighScore},
+    ui::prelude::*,
+};
+
+use super::{name_entry::spawn_name_entry, Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Winner), enter_winner.after(record_run));
+
+    app.register_type::<WinnerAction>();
+    app.add_systems(
+        Update,
+        handle_winner_action.run_if(in_state(Screen::Winner)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum WinnerAction {
+    Rematch,
+    Title,
+}
+
+fn enter_winner(
+    mut commands: Commands,
+    versus_result: Res<VersusResult>,
+    new_high_score: Option<Res<NewHighScore>>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Winner))
+        .with_children(|children| {
+            match versus_result.winner {
+                Some(index) => children.header(format!("Player {} wins!", index + 1)),
+                None => children.header("Draw!"),
+            };
+            for (index, score) in &versus_result.scores {
+                children.label(format!(
+                    "Player {}: {} points, {} lines",
+                    index + 1,
+                    score.points,
+                    score.lines
+                ));
+            }
+            if let Some(new_high_score) = &new_high_score {
+                spawn_name_entry(children, new_high_score);
+            }
+
+            children.button("Rematch").insert(WinnerAction::Rematch);
+            children.button("Title").insert(WinnerAction::Title);
+        });
+}
+
+fn handle_winner_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&WinnerAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                WinnerAction::Rematch => next_screen.set(Screen::Playing),
+                WinnerAction::Title => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
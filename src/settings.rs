@@ -0,0 +1,321 @@
+//! User preferences, loaded from the platform config directory before the app is built and saved
+//! whenever [`Settings`] changes.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::audio::channel::AudioChannels;
+use crate::game::input::{Action, InputBinding, InputMap};
+use crate::game::platter::glyph::GlyphOverlay;
+use crate::game::shake::ScreenShakeSettings;
+use crate::ui::theme::{ThemePreset, Themes};
+use crate::util::storage::{load_ron_or_default, project_dirs, write_atomically};
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_resource::<SettingsStorage>();
+    app.init_resource::<Settings>();
+    app.add_systems(
+        PreUpdate,
+        apply_settings.run_if(resource_changed::<Settings>),
+    );
+    app.add_systems(
+        Last,
+        save_settings
+            .run_if(resource_changed::<Settings>.and_then(not(resource_added::<Settings>))),
+    );
+}
+
+/// Version written by this build, bump it together with a new entry in [`MIGRATIONS`].
+pub const SETTINGS_VERSION: u32 = 4;
+
+/// Upgrades settings written by an older build in place.
+type Migration = fn(&mut Settings);
+
+/// `MIGRATIONS[i]` upgrades version `i + 1` to `i + 2`. Renamed fields keep loading through
+/// `#[serde(alias)]`, migrations convert values whose meaning changed.
+const MIGRATIONS: &[Migration] = &[bind_pause, move_player_two_off_arrows, bind_debug_actions];
+
+/// Version 2 added [`Action::Pause`], saved keybinds don't have it yet.
+fn bind_pause(settings: &mut Settings) {
+    if settings.keybinds.bindings(Action::Pause).is_empty() {
+        settings
+            .keybinds
+            .bind(Action::Pause, KeyCode::Escape)
+            .bind(Action::Pause, GamepadButtonType::Start);
+    }
+}
+
+/// Version 3 moved the spin keys of player two off the arrows, which also move.
+fn move_player_two_off_arrows(settings: &mut Settings) {
+    for (action, arrow, key) in [
+        (Action::SpinLeft(1), KeyCode::ArrowLeft, KeyCode::KeyJ),
+        (Action::SpinRight(1), KeyCode::ArrowRight, KeyCode::KeyK),
+    ] {
+        if settings
+            .keybinds
+            .bindings(action)
+            .contains(&InputBinding::Key(arrow))
+        {
+            settings.keybinds.unbind(action, arrow).bind(action, key);
+        }
+    }
+}
+
+/// Version 4 moved the debug spawn and garbage keys into the keybinds.
+fn bind_debug_actions(settings: &mut Settings) {
+    for (action, key) in [
+        (Action::SpawnBomb, KeyCode::KeyB),
+        (Action::SpawnLocked, KeyCode::KeyL),
+        (Action::RaiseGarbage, KeyCode::KeyG),
+    ] {
+        if settings.keybinds.bindings(action).is_empty() {
+            settings.keybinds.bind(action, key);
+        }
+    }
+}
+
+#[derive(
+    Resource,
+    Debug,
+    SmartDefault,
+    Clone,
+    PartialEq,
+    Reflect,
+    Serialize,
+    Deserialize,
+    AutoRegisterType,
+)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    #[default(SETTINGS_VERSION)]
+    pub version: u32,
+    /// `0.0..=1.0`, applied to [`GlobalVolume`].
+    #[default(0.3)]
+    pub master_volume: f32,
+    /// Channel volumes, see [`AudioChannels`].
+    #[default(1.0)]
+    pub music_volume: f32,
+    #[default(1.0)]
+    pub sfx_volume: f32,
+    #[default(1.0)]
+    pub ui_volume: f32,
+    /// Lower the music while important sounds play.
+    #[default(true)]
+    pub ducking: bool,
+    pub window_mode: WindowMode,
+    pub theme: ThemePreset,
+    /// Colour-blind glyphs, see [`GlyphOverlay`].
+    pub glyphs: bool,
+    /// See [`ScreenShakeSettings::intensity`].
+    #[default(1.0)]
+    pub screen_shake: f32,
+    pub keybinds: InputMap,
+}
+
+impl Settings {
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        let mut settings = ron::from_str::<Self>(ron)?;
+        settings.migrate(MIGRATIONS);
+        Ok(settings)
+    }
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+    fn migrate(&mut self, migrations: &[Migration]) {
+        let latest = migrations.len() as u32 + 1;
+        if self.version > latest {
+            log::warn!(
+                "settings version {} is newer than {latest}, unknown fields are dropped",
+                self.version
+            );
+        }
+        for (from, migration) in (1..).zip(migrations) {
+            if self.version > from {
+                continue;
+            }
+            log::debug!("migrating settings from version {from}");
+            migration(self);
+        }
+        self.version = latest;
+    }
+}
+
+/// Where [`Settings`] are stored, `None` where there's no file system (wasm).
+#[derive(Resource, Debug, Clone)]
+pub struct SettingsStorage {
+    path: Option<PathBuf>,
+}
+
+impl Default for SettingsStorage {
+    fn default() -> Self {
+        let path = project_dirs().map(|dirs| dirs.config_dir().join("settings.ron"));
+        Self { path }
+    }
+}
+
+impl SettingsStorage {
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+    /// See [`load_ron_or_default`].
+    pub fn load(&self) -> Settings {
+        load_ron_or_default(self.path(), Settings::from_ron)
+    }
+    pub fn save(&self, settings: &Settings) -> io::Result<()> {
+        let Some(path) = self.path() else {
+            return Ok(());
+        };
+        let ron = settings
+            .to_ron()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        write_atomically(path, &ron)
+    }
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut audio_channels: ResMut<AudioChannels>,
+    mut window_q: Query<&mut Window, With<PrimaryWindow>>,
+    mut input_map: ResMut<InputMap>,
+    mut themes: ResMut<Themes>,
+    mut glyph_overlay: ResMut<GlyphOverlay>,
+    mut screen_shake: ResMut<ScreenShakeSettings>,
+) {
+    global_volume.volume = Volume::new(settings.master_volume);
+    audio_channels.music = settings.music_volume;
+    audio_channels.sfx = settings.sfx_volume;
+    audio_channels.ui = settings.ui_volume;
+    audio_channels.ducking.enabled = settings.ducking;
+    for mut window in window_q.iter_mut() {
+        if window.mode != settings.window_mode {
+            window.mode = settings.window_mode;
+        }
+    }
+    input_map.set_if_neq(settings.keybinds.clone());
+    // avoids a spurious ThemeChanged
+    if themes.current != settings.theme {
+        themes.current = settings.theme;
+    }
+    if glyph_overlay.enabled != settings.glyphs {
+        glyph_overlay.enabled = settings.glyphs;
+    }
+    screen_shake.intensity = settings.screen_shake;
+}
+
+fn save_settings(settings: Res<Settings>, storage: Res<SettingsStorage>) {
+    if let Err(err) = storage.save(&settings) {
+        log::warn!("failed to save settings: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::game::input::Action;
+    use crate::util::storage::test_support::temp_file_path;
+
+    use super::*;
+
+    fn temp_storage(name: &str) -> SettingsStorage {
+        SettingsStorage::at(temp_file_path(&format!("settings_{name}.ron")))
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let storage = temp_storage("save_and_load");
+        let mut settings = Settings {
+            master_volume: 0.7,
+            window_mode: WindowMode::BorderlessFullscreen,
+            theme: ThemePreset::HighContrast,
+            ..default()
+        };
+        settings
+            .keybinds
+            .clear(Action::Drop(0))
+            .bind(Action::Drop(0), KeyCode::KeyX);
+        storage.save(&settings).unwrap();
+        assert_eq!(storage.load(), settings);
+    }
+
+    #[test]
+    fn test_corrupt_file_falls_back_to_defaults() {
+        let storage = temp_storage("corrupt");
+        let path = storage.path().unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "(master_volume: ").unwrap();
+        assert_eq!(storage.load(), Settings::default());
+        assert!(!path.exists());
+        assert!(path.with_extension("ron.corrupt").exists());
+    }
+
+    #[test]
+    fn test_migrations_run_in_order() {
+        let mut settings = ron::from_str::<Settings>("(version: 1, master_volume: 1.0)").unwrap();
+        assert_eq!(settings.screen_shake, 1.0);
+        settings.migrate(&[
+            |settings| settings.master_volume /= 2.0,
+            |settings| settings.screen_shake = settings.master_volume,
+        ]);
+        assert_eq!(settings.version, 3);
+        assert_eq!(settings.screen_shake, 0.5);
+        // already up to date
+        settings.migrate(&[|settings| settings.master_volume = 0.0, |_| {}]);
+        assert_eq!(settings.master_volume, 0.5);
+    }
+
+    #[test]
+    fn test_migration_binds_pause() {
+        let settings = Settings::from_ron("(version: 1, keybinds: [])").unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert!(!settings.keybinds.bindings(Action::Pause).is_empty());
+    }
+
+    #[test]
+    fn test_migration_moves_player_two_off_arrows() {
+        let settings = Settings::from_ron(
+            "(version: 2, keybinds: [(SpinLeft(1), [Key(ArrowLeft), Key(KeyZ)]), (Drop(1), [Key(Enter)])])",
+        )
+        .unwrap();
+        assert_eq!(
+            settings.keybinds.bindings(Action::SpinLeft(1)),
+            &[
+                InputBinding::Key(KeyCode::KeyZ),
+                InputBinding::Key(KeyCode::KeyJ)
+            ]
+        );
+        assert_eq!(
+            settings.keybinds.bindings(Action::Drop(1)),
+            &[InputBinding::Key(KeyCode::Enter)]
+        );
+    }
+
+    #[test]
+    fn test_migration_binds_debug_actions() {
+        let settings = Settings::from_ron("(version: 3, keybinds: [])").unwrap();
+        assert_eq!(
+            settings.keybinds.bindings(Action::RaiseGarbage),
+            &[InputBinding::Key(KeyCode::KeyG)]
+        );
+    }
+}
@@ -46,7 +46,9 @@ use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
 use crate::game::camera::{Focus, MainCamera, MainCameraControllerSet};
 use crate::game::spawn::level::SpawnLevel;
+use crate::game::util::debug_draw::DebugDrawGizmos;
 use crate::screen::Screen;
+use crate::util::prototype_mesh_manager::PrototypeMeshManager;
 
 pub(crate) fn plugin(app: &mut App) {
     Types.register_types(app);
@@ -112,6 +114,8 @@ impl UiState {
                 EguiWindow::Inspector,
                 EguiWindow::Physics,
                 EguiWindow::GameState,
+                EguiWindow::Meshes,
+                EguiWindow::DebugDraw,
             ],
         );
         let [game, _hierarchy] = tree.split_left(game, 0.2, vec![EguiWindow::Hierarchy]);
@@ -156,6 +160,8 @@ enum EguiWindow {
     Inspector,
     Physics,
     GameState,
+    Meshes,
+    DebugDraw,
 }
 
 #[derive(Debug)]
@@ -456,6 +462,46 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                         .set(Screen::BeforePlaying);
                 }
             }
+            EguiWindow::Meshes => {
+                let mut prototype_mesh_manager = self.world.resource_mut::<PrototypeMeshManager>();
+                ui.heading("Prototype Meshes");
+                let stats = prototype_mesh_manager.stats();
+                ui.label(format!("cached: {}", stats.cached));
+                ui.label(format!("in use: {}", stats.in_use));
+                ui.label(format!("created: {}", stats.created));
+                ui.label(format!("reused: {}", stats.reused));
+                ui.label(format!("collected: {}", stats.collected));
+                if ui.button("Collect Unused").clicked() {
+                    prototype_mesh_manager.collect_unused();
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (id, entry) in prototype_mesh_manager.iter() {
+                        ui.label(format!(
+                            "{id}: {} users, {} requests",
+                            entry.users(),
+                            entry.requests()
+                        ));
+                    }
+                });
+            }
+            EguiWindow::DebugDraw => {
+                let mut config_store = self.world.resource_mut::<GizmoConfigStore>();
+                let (config, debug_draw_gizmos) = config_store.config_mut::<DebugDrawGizmos>();
+                ui.checkbox(&mut config.enabled, "Enabled");
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (name, scope) in debug_draw_gizmos.scopes_mut() {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut scope.visible, name.to_string());
+                            ui.label(format!("{} primitives", scope.primitive_count()));
+                            if ui.button("Clear").clicked() {
+                                scope.clear();
+                            }
+                        });
+                    }
+                });
+            }
         }
     }
 
@@ -8,6 +8,7 @@ use bevy::prelude::*;
 pub mod inspector;
 pub mod interaction;
 pub mod palette;
+pub mod theme;
 mod widgets;
 
 pub mod prelude {
@@ -19,5 +20,5 @@ pub mod prelude {
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, theme::plugin));
 }
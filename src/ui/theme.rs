@@ -0,0 +1,345 @@
+//! Switchable colour themes for blocks, platters and UI.
+
+use bevy::color::palettes::css::{
+    BLACK, BLUE, DIM_GRAY, GRAY, GREEN, LIGHT_GRAY, ORANGE, PURPLE, RED, WHITE, YELLOW,
+};
+use bevy::color::palettes::tailwind::{
+    BLUE_600, CYAN_300, CYAN_600, GRAY_200, GRAY_400, GREEN_600, ORANGE_500, PURPLE_600, RED_600,
+    SLATE_700, YELLOW_500,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
+use internal_shared::register_type_binder::RegisterTypeBinder;
+
+use crate::game::platter::value::{CellValue, InnerValue};
+use crate::ui::interaction::InteractionPalette;
+use crate::ui::palette::*;
+
+pub(super) fn plugin(app: &mut App) {
+    Types.register_types(app);
+    app.init_asset::<Theme>();
+    app.register_asset_reflect::<Theme>();
+    app.init_resource::<Themes>();
+    app.add_event::<ThemeChanged>();
+    app.add_systems(
+        PreUpdate,
+        (
+            detect_theme_changed,
+            apply_ui_theme.run_if(on_event::<ThemeChanged>()),
+        )
+            .chain(),
+    );
+    app.add_systems(Update, apply_ui_theme_to_added);
+}
+
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    AutoRegisterType,
+)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 3] = [
+        ThemePreset::Dark,
+        ThemePreset::Light,
+        ThemePreset::HighContrast,
+    ];
+    pub fn next(self) -> Self {
+        match self {
+            ThemePreset::Dark => ThemePreset::Light,
+            ThemePreset::Light => ThemePreset::HighContrast,
+            ThemePreset::HighContrast => ThemePreset::Dark,
+        }
+    }
+    fn theme(self) -> Theme {
+        match self {
+            ThemePreset::Dark => Theme::default(),
+            ThemePreset::Light => Theme {
+                blocks: BlockColors {
+                    red_z: RED_600.into(),
+                    green_s: GREEN_600.into(),
+                    yellow_o: YELLOW_500.into(),
+                    purple_t: PURPLE_600.into(),
+                    blue_j: BLUE_600.into(),
+                    orange_l: ORANGE_500.into(),
+                    cyan_i: CYAN_600.into(),
+                },
+                bomb: BLACK.into(),
+                garbage: GRAY_400.into(),
+                locked_tint: WHITE.into(),
+                main_color: GRAY_200.into(),
+                initial_segment_color: WHITE.into(),
+                glyph: WHITE.into(),
+                ui: UiColors {
+                    button_hovered_background: Color::srgb(0.686, 0.788, 0.925),
+                    button_pressed_background: Color::srgb(0.541, 0.671, 0.871),
+                    button_text: SLATE_700.into(),
+                    label_text: SLATE_700.into(),
+                    header_text: SLATE_700.into(),
+                    node_background: Color::srgb(0.827, 0.878, 0.949),
+                },
+            },
+            ThemePreset::HighContrast => Theme {
+                blocks: BlockColors {
+                    red_z: RED.into(),
+                    green_s: Color::srgb(0.0, 1.0, 0.0),
+                    yellow_o: YELLOW.into(),
+                    purple_t: Color::srgb(1.0, 0.0, 1.0),
+                    blue_j: Color::srgb(0.2, 0.4, 1.0),
+                    orange_l: ORANGE.into(),
+                    cyan_i: Color::srgb(0.0, 1.0, 1.0),
+                },
+                bomb: WHITE.into(),
+                garbage: LIGHT_GRAY.into(),
+                locked_tint: BLACK.into(),
+                main_color: BLACK.into(),
+                initial_segment_color: BLACK.into(),
+                glyph: BLACK.into(),
+                ui: UiColors {
+                    button_hovered_background: Color::srgb(0.3, 0.3, 0.3),
+                    button_pressed_background: WHITE.into(),
+                    button_text: YELLOW.into(),
+                    label_text: WHITE.into(),
+                    header_text: YELLOW.into(),
+                    node_background: BLACK.into(),
+                },
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct BlockColors {
+    pub red_z: Color,
+    pub green_s: Color,
+    pub yellow_o: Color,
+    pub purple_t: Color,
+    pub blue_j: Color,
+    pub orange_l: Color,
+    pub cyan_i: Color,
+}
+
+impl BlockColors {
+    pub fn get(&self, value: InnerValue) -> Color {
+        match value {
+            InnerValue::RedZ => self.red_z,
+            InnerValue::GreenS => self.green_s,
+            InnerValue::YellowO => self.yellow_o,
+            InnerValue::PurpleT => self.purple_t,
+            InnerValue::BlueJ => self.blue_j,
+            InnerValue::OrangeL => self.orange_l,
+            InnerValue::CyanI => self.cyan_i,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct UiColors {
+    pub button_hovered_background: Color,
+    pub button_pressed_background: Color,
+    pub button_text: Color,
+    pub label_text: Color,
+    pub header_text: Color,
+    pub node_background: Color,
+}
+
+#[derive(Asset, Debug, Clone, PartialEq, Reflect)]
+pub struct Theme {
+    pub blocks: BlockColors,
+    pub bomb: Color,
+    pub garbage: Color,
+    /// Mixed into the block colour of locked segments.
+    pub locked_tint: Color,
+    pub main_color: Color,
+    pub initial_segment_color: Color,
+    /// Colour of the colour-blind glyphs drawn over filled segments.
+    pub glyph: Color,
+    pub ui: UiColors,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            blocks: BlockColors {
+                red_z: RED.into(),
+                green_s: GREEN.into(),
+                yellow_o: YELLOW.into(),
+                purple_t: PURPLE.into(),
+                blue_j: BLUE.into(),
+                orange_l: ORANGE.into(),
+                cyan_i: CYAN_300.into(),
+            },
+            bomb: WHITE.into(),
+            garbage: GRAY.into(),
+            locked_tint: DIM_GRAY.into(),
+            main_color: Color::BLACK,
+            initial_segment_color: Color::default(),
+            glyph: BLACK.into(),
+            ui: UiColors {
+                button_hovered_background: BUTTON_HOVERED_BACKGROUND,
+                button_pressed_background: BUTTON_PRESSED_BACKGROUND,
+                button_text: BUTTON_TEXT,
+                label_text: LABEL_TEXT,
+                header_text: HEADER_TEXT,
+                node_background: NODE_BACKGROUND,
+            },
+        }
+    }
+}
+
+impl Theme {
+    pub fn cell_color(&self, value: Option<CellValue>) -> Color {
+        match value {
+            None => self.initial_segment_color,
+            Some(CellValue::Block(inner)) => self.blocks.get(inner),
+            Some(CellValue::Bomb) => self.bomb,
+            Some(CellValue::Locked(inner)) => self.blocks.get(inner).mix(&self.locked_tint, 0.6),
+            Some(CellValue::Garbage) => self.garbage,
+        }
+    }
+}
+
+/// Handles of the preset themes, the one in `current` is applied.
+#[derive(Resource, Debug, Clone, Reflect, AutoRegisterType)]
+#[reflect(Resource)]
+pub struct Themes {
+    pub current: ThemePreset,
+    handles: HashMap<ThemePreset, Handle<Theme>>,
+}
+
+impl FromWorld for Themes {
+    fn from_world(world: &mut World) -> Self {
+        let mut assets = world.resource_mut::<Assets<Theme>>();
+        Self {
+            current: ThemePreset::default(),
+            handles: ThemePreset::ALL
+                .into_iter()
+                .map(|preset| (preset, assets.add(preset.theme())))
+                .collect(),
+        }
+    }
+}
+
+impl Themes {
+    pub fn handle(&self, preset: ThemePreset) -> &Handle<Theme> {
+        &self.handles[&preset]
+    }
+}
+
+/// Read access to the theme in use.
+#[derive(SystemParam)]
+pub struct CurrentTheme<'w> {
+    themes: Res<'w, Themes>,
+    assets: Res<'w, Assets<Theme>>,
+}
+
+impl CurrentTheme<'_> {
+    pub fn get(&self) -> &Theme {
+        self.assets
+            .get(self.themes.handle(self.themes.current))
+            .expect("theme asset removed")
+    }
+}
+
+/// Sent when a different theme was selected or the current one was edited.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct ThemeChanged;
+
+/// UI colour role of a themed entity.
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Reflect, AutoRegisterType)]
+#[reflect(Component)]
+pub enum ThemeColor {
+    ButtonText,
+    LabelText,
+    HeaderText,
+    NodeBackground,
+}
+
+#[derive(RegisterTypeBinder)]
+pub struct Types;
+
+fn detect_theme_changed(
+    themes: Res<Themes>,
+    mut asset_events: EventReader<AssetEvent<Theme>>,
+    mut theme_changed: EventWriter<ThemeChanged>,
+) {
+    let current = themes.handle(themes.current).id();
+    let edited = asset_events.read().any(|event| event.is_modified(current));
+    if edited || (themes.is_changed() && !themes.is_added()) {
+        theme_changed.send(ThemeChanged);
+    }
+}
+
+fn apply_theme_color(
+    theme: &Theme,
+    role: ThemeColor,
+    text: Option<Mut<Text>>,
+    background: Option<Mut<BackgroundColor>>,
+    palette: Option<Mut<InteractionPalette>>,
+) {
+    let ui = theme.ui;
+    if let Some(mut text) = text {
+        let color = match role {
+            ThemeColor::ButtonText => ui.button_text,
+            ThemeColor::LabelText => ui.label_text,
+            ThemeColor::HeaderText => ui.header_text,
+            ThemeColor::NodeBackground => return,
+        };
+        for section in text.sections.iter_mut() {
+            section.style.color = color;
+        }
+    }
+    if role != ThemeColor::NodeBackground {
+        return;
+    }
+    if let Some(mut background) = background {
+        background.0 = ui.node_background;
+    }
+    if let Some(mut palette) = palette {
+        palette.none = ui.node_background;
+        palette.hovered = ui.button_hovered_background;
+        palette.pressed = ui.button_pressed_background;
+    }
+}
+
+type ThemedQueryData = (
+    &'static ThemeColor,
+    Option<&'static mut Text>,
+    Option<&'static mut BackgroundColor>,
+    Option<&'static mut InteractionPalette>,
+);
+
+fn apply_ui_theme(theme: CurrentTheme, mut themed_q: Query<ThemedQueryData>) {
+    let theme = theme.get();
+    for (&role, text, background, palette) in themed_q.iter_mut() {
+        apply_theme_color(theme, role, text, background, palette);
+    }
+}
+
+fn apply_ui_theme_to_added(
+    theme: CurrentTheme,
+    mut themed_q: Query<ThemedQueryData, Added<ThemeColor>>,
+) {
+    let theme = theme.get();
+    for (&role, text, background, palette) in themed_q.iter_mut() {
+        apply_theme_color(theme, role, text, background, palette);
+    }
+}
@@ -2,7 +2,7 @@
 
 use bevy::{ecs::system::EntityCommands, prelude::*, ui::Val::*};
 
-use super::{interaction::InteractionPalette, palette::*};
+use super::{interaction::InteractionPalette, palette::*, theme::ThemeColor};
 
 /// An extension trait for spawning UI widgets.
 pub trait Widgets {
@@ -36,6 +36,7 @@ impl<T: Spawn> Widgets for T {
                 hovered: BUTTON_HOVERED_BACKGROUND,
                 pressed: BUTTON_PRESSED_BACKGROUND,
             },
+            ThemeColor::NodeBackground,
         ));
         entity.with_children(|children| {
             children.spawn((
@@ -48,6 +49,7 @@ impl<T: Spawn> Widgets for T {
                         ..default()
                     },
                 ),
+                ThemeColor::ButtonText,
             ));
         });
         entity
@@ -67,6 +69,7 @@ impl<T: Spawn> Widgets for T {
                 background_color: BackgroundColor(NODE_BACKGROUND),
                 ..default()
             },
+            ThemeColor::NodeBackground,
         ));
         entity.with_children(|children| {
             children.spawn((
@@ -79,6 +82,7 @@ impl<T: Spawn> Widgets for T {
                         ..default()
                     },
                 ),
+                ThemeColor::HeaderText,
             ));
         });
         entity
@@ -108,6 +112,7 @@ impl<T: Spawn> Widgets for T {
                         ..default()
                     },
                 ),
+                ThemeColor::LabelText,
             ));
         });
         entity
@@ -13,6 +13,7 @@ pub(crate) mod color_id;
 pub(crate) mod color_material_manager;
 pub(crate) mod prototype_mesh_manager;
 pub(crate) mod ref_ext;
+pub(crate) mod storage;
 pub(crate) mod string;
 
 pub(super) fn plugin(app: &mut App) {
@@ -1,31 +1,136 @@
-use std::fmt::{Display, Formatter};
+use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
 
 use bevy::app::App;
-use bevy::prelude::{Assets, Handle, Mesh, Reflect, ReflectResource, ResMut, Resource};
+use bevy::prelude::{
+    Assets, Color, Handle, IntoSystemConfigs, Last, Mesh, Reflect, ReflectResource, ResMut,
+    Resource, Vec2,
+};
+use bevy::time::common_conditions::on_timer;
 use bevy::utils::HashMap;
 use derive_more::Display;
 
 use internal_proc_macros::{AutoRegisterType, RegisterTypeBinder};
 
-use crate::util::string::AnyString;
-
 pub(super) fn plugin(app: &mut App) {
     Types.register_types(app);
     app.init_resource::<PrototypeMeshManager>();
+    app.add_systems(
+        Last,
+        collect_unused_meshes.run_if(on_timer(COLLECT_INTERVAL)),
+    );
+}
+
+const COLLECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Hash of a prototype, cheap to store and compare no matter how big the prototype is.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq, Display, Reflect, AutoRegisterType)]
+#[display(fmt = "{:016x}", _0)]
+pub struct PrototypeMeshId(u64);
+
+impl PrototypeMeshId {
+    pub fn from_hash(value: &impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        Self(hasher.finish())
+    }
 }
 
-pub type PrototypeMeshId = AnyString;
+/// Everything a prototype mesh is built from. Floats are kept as their bits, so equal keys always
+/// build the same mesh no matter how the values would print.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct PrototypeMeshKey {
+    /// Keeps different prototypes built from the same values apart.
+    kind: &'static str,
+    data: Vec<u32>,
+}
+
+impl PrototypeMeshKey {
+    pub fn new(kind: &'static str) -> Self {
+        Self { kind, data: vec![] }
+    }
+    pub fn with_u32(mut self, value: u32) -> Self {
+        self.data.push(value);
+        self
+    }
+    pub fn with_usize(self, value: usize) -> Self {
+        let value = value as u64;
+        self.with_u32(value as u32).with_u32((value >> 32) as u32)
+    }
+    pub fn with_f32(self, value: f32) -> Self {
+        self.with_u32(value.to_bits())
+    }
+    pub fn with_color(self, color: Color) -> Self {
+        color
+            .to_linear()
+            .to_f32_array()
+            .into_iter()
+            .fold(self, Self::with_f32)
+    }
+    pub fn with_vertices(self, vertices: &[Vec2]) -> Self {
+        vertices
+            .iter()
+            .fold(self.with_usize(vertices.len()), |key, vertex| {
+                key.with_f32(vertex.x).with_f32(vertex.y)
+            })
+    }
+    pub fn id(&self) -> PrototypeMeshId {
+        PrototypeMeshId::from_hash(self)
+    }
+    /// Second hash, independent of [`Self::id`], telling apart keys whose ids collide.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (FINGERPRINT_SEED, self).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+const FINGERPRINT_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
 
 pub trait PrototypeMesh {
-    fn get_id(&self) -> PrototypeMeshId;
+    fn get_key(&self) -> PrototypeMeshKey;
     fn get_mesh(&self) -> Mesh;
 }
 
+#[derive(Debug, Default, Clone, Reflect)]
+pub struct PrototypeMeshEntry {
+    handle: Handle<Mesh>,
+    /// Compared on every lookup, two keys sharing an id would otherwise share a mesh.
+    fingerprint: u64,
+    /// Number of times this mesh was handed out.
+    requests: usize,
+}
+
+impl PrototypeMeshEntry {
+    /// Strong handles held outside of the manager.
+    pub fn users(&self) -> usize {
+        match &self.handle {
+            Handle::Strong(handle) => Arc::strong_count(handle) - 1,
+            Handle::Weak(_) => 0,
+        }
+    }
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Reflect)]
+pub struct PrototypeMeshStats {
+    pub cached: usize,
+    pub in_use: usize,
+    pub created: usize,
+    pub reused: usize,
+    pub collected: usize,
+}
+
 #[derive(Resource, Debug, Default, Clone, Reflect, AutoRegisterType)]
 #[reflect(Resource)]
 pub struct PrototypeMeshManager {
-    mesh_map: HashMap<PrototypeMeshId, Handle<Mesh>>,
+    /// Keys whose ids collide share a bucket.
+    mesh_map: HashMap<PrototypeMeshId, Vec<PrototypeMeshEntry>>,
+    stats: PrototypeMeshStats,
 }
 
 impl PrototypeMeshManager {
@@ -35,12 +140,90 @@ impl PrototypeMeshManager {
         prototype: impl Into<&'a T>,
     ) -> Handle<Mesh> {
         let prototype = prototype.into();
+        let key = prototype.get_key();
+        let fingerprint = key.fingerprint();
+        let bucket = self.mesh_map.entry(key.id()).or_default();
+        let index = match bucket
+            .iter()
+            .position(|entry| entry.fingerprint == fingerprint)
+        {
+            Some(index) => index,
+            None => {
+                self.stats.created += 1;
+                bucket.push(PrototypeMeshEntry {
+                    handle: meshes.add(prototype.get_mesh()),
+                    fingerprint,
+                    requests: 0,
+                });
+                bucket.len() - 1
+            }
+        };
+        let entry = &mut bucket[index];
+        if entry.requests > 0 {
+            self.stats.reused += 1;
+        }
+        entry.requests += 1;
+        entry.handle.clone()
+    }
+    /// Drops every mesh that is no longer used outside of the manager, returns how many were dropped.
+    pub fn collect_unused(&mut self) -> usize {
+        let before = self.entries().count();
+        self.mesh_map.retain(|_, bucket| {
+            bucket.retain(|entry| entry.users() > 0);
+            !bucket.is_empty()
+        });
+        let collected = before - self.entries().count();
+        self.stats.collected += collected;
+        collected
+    }
+    pub fn stats(&self) -> PrototypeMeshStats {
+        PrototypeMeshStats {
+            cached: self.entries().count(),
+            in_use: self.entries().filter(|entry| entry.users() > 0).count(),
+            ..self.stats
+        }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&PrototypeMeshId, &PrototypeMeshEntry)> {
         self.mesh_map
-            .entry(prototype.get_id())
-            .or_insert_with(|| meshes.add(prototype.get_mesh()))
-            .clone()
+            .iter()
+            .flat_map(|(id, bucket)| bucket.iter().map(move |entry| (id, entry)))
+    }
+    fn entries(&self) -> impl Iterator<Item = &PrototypeMeshEntry> {
+        self.mesh_map.values().flatten()
     }
 }
 
 #[derive(RegisterTypeBinder)]
 pub struct Types;
+
+fn collect_unused_meshes(mut prototype_mesh_manager: ResMut<PrototypeMeshManager>) {
+    let collected = prototype_mesh_manager.collect_unused();
+    if collected > 0 {
+        log::debug!("collected {collected} unused prototype meshes");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_compares_bits() {
+        let key = |value: f32| {
+            PrototypeMeshKey::new("test")
+                .with_f32(value)
+                .with_vertices(&[Vec2::ONE, Vec2::X])
+        };
+        assert_eq!(key(1.5), key(1.5));
+        assert_eq!(key(1.5).id(), key(1.5).id());
+        assert_eq!(key(1.5).fingerprint(), key(1.5).fingerprint());
+        assert_ne!(key(1.5).fingerprint(), key(2.5).fingerprint());
+        assert_ne!(key(0.0), key(-0.0));
+        assert_ne!(
+            key(1.5),
+            PrototypeMeshKey::new("other")
+                .with_f32(1.5)
+                .with_vertices(&[Vec2::ONE, Vec2::X])
+        );
+    }
+}
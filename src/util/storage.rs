@@ -0,0 +1,69 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use directories::ProjectDirs;
+
+/// Platform directories for the game's files, `None` where there's no file system (wasm).
+pub fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "bevy_game_jam_5_prototypes")
+}
+
+/// Writes to a temporary file next to `path` first so a crash mid-write can't corrupt it.
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("ron.tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(tmp_path, path)
+}
+
+/// Parses the file at `path` with `parse`, falling back to defaults when there's no path, no file
+/// or it can't be read. A file that fails to parse is kept next to it with a `.corrupt` extension
+/// instead of being overwritten by the next save.
+pub fn load_ron_or_default<T: Default>(
+    path: Option<&Path>,
+    parse: impl FnOnce(&str) -> Result<T, ron::error::SpannedError>,
+) -> T {
+    let Some(path) = path else {
+        return T::default();
+    };
+    let ron = match fs::read_to_string(path) {
+        Ok(ron) => ron,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            log::debug!("nothing at {}, using defaults", path.display());
+            return T::default();
+        }
+        Err(err) => {
+            log::warn!("failed to read {}: {err}", path.display());
+            return T::default();
+        }
+    };
+    match parse(&ron) {
+        Ok(value) => value,
+        Err(err) => {
+            log::warn!("corrupt {}: {err}", path.display());
+            if let Err(err) = fs::rename(path, path.with_extension("ron.corrupt")) {
+                log::warn!("failed to back up {}: {err}", path.display());
+            }
+            T::default()
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_support {
+    use std::path::PathBuf;
+
+    /// A path in a temporary directory of this test run, shared by the storage tests.
+    pub fn temp_file_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "{}_test_{}",
+                env!("CARGO_PKG_NAME"),
+                std::process::id()
+            ))
+            .join(name)
+    }
+}